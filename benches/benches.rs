@@ -1,11 +1,16 @@
 #![feature(test)]
+#![allow(clippy::needless_return)]
 extern crate test;
 extern crate dstu4145;
 
 use test::Bencher;
 
 use dstu4145::gf2m;
+use dstu4145::gf2m64;
+use dstu4145::field::BinaryField;
+use dstu4145::onb::OnbField;
 use dstu4145::curve;
+use dstu4145::bitslice;
 use dstu4145::dstu_params;
 
 
@@ -34,6 +39,19 @@ fn bench_field_reduce(b: &mut Bencher) {
 }
 
 
+#[bench]
+fn bench_field_reduce_bitwise(b: &mut Bencher) {
+    let mod257 = gf2m::compute_modulus(257, 12, 0, 0);
+    let value_a = gf2m::parse_hex(b"aff3ee09cb429284985849e20de5742e194aa631490f62ba88702505629a65890");
+    let value_b = gf2m::parse_hex(b"a3391f6f341d627ab958fc4223ee8871e336c8d9dda30f407c369268363f0cccb");
+
+    let long_value = gf2m::mul(&value_a, &value_b);
+
+    b.iter(|| {
+        gf2m::reduce_bitwise(&long_value, &mod257);
+    });
+}
+
 #[bench]
 fn bench_field_reduce_2(b: &mut Bencher) {
     let mod257 = gf2m::compute_modulus(257, 12, 0, 0);
@@ -74,6 +92,116 @@ fn bench_field_mul_2x2(b: &mut Bencher) {
     });
 }
 
+#[bench]
+fn bench_field_mul_karatsuba(b: &mut Bencher) {
+    let value_a = gf2m::parse_hex(b"aff3ee09cb429284985849e20de5742e194aa631490f62ba88702505629a65890");
+    let value_b = gf2m::parse_hex(b"a3391f6f341d627ab958fc4223ee8871e336c8d9dda30f407c369268363f0cccb");
+
+    b.iter(|| {
+        gf2m::mul_karatsuba(&value_a, &value_b);
+    });
+}
+
+#[bench]
+fn bench_field_mul_2x2_431(b: &mut Bencher) {
+    let value_a = gf2m::parse_hex(b"44d59ab11eebf43534911d05992fc24fa8c6d0da68276eaf1bcde5dc6767705b527b007e700da85f1dc5f550392cabca624bbdf91bb7");
+    let value_b = gf2m::parse_hex(b"6edb5b3e38bf271233378ac0fe3990289007928f56beb38a4f63843b9995afdd88a09c7da6935a4b43b0afde65a4ca9c159d72ed5275");
+
+    b.iter(|| {
+        gf2m::mul(&value_a, &value_b);
+    });
+}
+
+#[bench]
+fn bench_field_mul_karatsuba_431(b: &mut Bencher) {
+    let value_a = gf2m::parse_hex(b"44d59ab11eebf43534911d05992fc24fa8c6d0da68276eaf1bcde5dc6767705b527b007e700da85f1dc5f550392cabca624bbdf91bb7");
+    let value_b = gf2m::parse_hex(b"6edb5b3e38bf271233378ac0fe3990289007928f56beb38a4f63843b9995afdd88a09c7da6935a4b43b0afde65a4ca9c159d72ed5275");
+
+    b.iter(|| {
+        gf2m::mul_karatsuba(&value_a, &value_b);
+    });
+}
+
+#[bench]
+fn bench_field_mul_fast(b: &mut Bencher) {
+    let value_a = gf2m::parse_hex(b"aff3ee09cb429284985849e20de5742e194aa631490f62ba88702505629a65890");
+    let value_b = gf2m::parse_hex(b"a3391f6f341d627ab958fc4223ee8871e336c8d9dda30f407c369268363f0cccb");
+
+    b.iter(|| {
+        gf2m::mul_fast(&value_a, &value_b);
+    });
+}
+
+#[bench]
+fn bench_binary_field_mul_163(b: &mut Bencher) {
+    let field = BinaryField::new(163, 7, 6, 3).unwrap();
+    let value_a = gf2m::parse_hex(b"72d867f93a93ac27df9ff01affe74885c8c540420");
+    let value_b = gf2m::parse_hex(b"0224a9c3947852b97c5599d5f4ab81122adc3fd9b");
+
+    b.iter(|| {
+        field.mul(&value_a, &value_b);
+    });
+}
+
+#[bench]
+fn bench_field64_mul(b: &mut Bencher) {
+    let value_a = gf2m64::from_field32(&gf2m::parse_hex(b"aff3ee09cb429284985849e20de5742e194aa631490f62ba88702505629a65890"));
+    let value_b = gf2m64::from_field32(&gf2m::parse_hex(b"a3391f6f341d627ab958fc4223ee8871e336c8d9dda30f407c369268363f0cccb"));
+
+    b.iter(|| {
+        gf2m64::mul(&value_a, &value_b);
+    });
+}
+
+/* Multiply and reduce without CLMUL, run with and without
+   `--features limb64` to compare 32-bit words and 64-bit limbs. */
+#[bench]
+fn bench_field_mul_sparse_257(b: &mut Bencher) {
+    let value_a: [u32; 9] = gf2m::parse_hex_words(b"aff3ee09cb429284985849e20de5742e194aa631490f62ba88702505629a65890");
+    let value_b: [u32; 9] = gf2m::parse_hex_words(b"a3391f6f341d627ab958fc4223ee8871e336c8d9dda30f407c369268363f0cccb");
+
+    b.iter(|| {
+        gf2m::mul_sparse_words(&value_a, &value_b, 257, 12, 0, 0);
+    });
+}
+
+#[bench]
+fn bench_field_mul_sparse_431(b: &mut Bencher) {
+    let value_a: [u32; 14] = gf2m::parse_hex_words(b"44d59ab11eebf43534911d05992fc24fa8c6d0da68276eaf1bcde5dc6767705b527b007e700da85f1dc5f550392cabca624bbdf91bb7");
+    let value_b: [u32; 14] = gf2m::parse_hex_words(b"6edb5b3e38bf271233378ac0fe3990289007928f56beb38a4f63843b9995afdd88a09c7da6935a4b43b0afde65a4ca9c159d72ed5275");
+
+    b.iter(|| {
+        gf2m::mul_sparse_words(&value_a, &value_b, 431, 5, 3, 1);
+    });
+}
+
+#[bench]
+fn bench_field_sqr_sparse_431(b: &mut Bencher) {
+    let value_a: [u32; 14] = gf2m::parse_hex_words(b"44d59ab11eebf43534911d05992fc24fa8c6d0da68276eaf1bcde5dc6767705b527b007e700da85f1dc5f550392cabca624bbdf91bb7");
+
+    b.iter(|| {
+        gf2m::sqr_sparse_words(&value_a, 431, 5, 3, 1);
+    });
+}
+
+#[bench]
+fn bench_field_sqr(b: &mut Bencher) {
+    let value_a = gf2m::parse_hex(b"aff3ee09cb429284985849e20de5742e194aa631490f62ba88702505629a65890");
+
+    b.iter(|| {
+        gf2m::sqr(&value_a);
+    });
+}
+
+#[bench]
+fn bench_field_sqr_fast(b: &mut Bencher) {
+    let value_a = gf2m::parse_hex(b"aff3ee09cb429284985849e20de5742e194aa631490f62ba88702505629a65890");
+
+    b.iter(|| {
+        gf2m::sqr_fast(&value_a);
+    });
+}
+
 #[bench]
 fn bench_field_neg(b: &mut Bencher) {
     let mod257 = gf2m::compute_modulus(257, 12, 0, 0);
@@ -85,70 +213,87 @@ fn bench_field_neg(b: &mut Bencher) {
 }
 
 #[bench]
-fn bench_point_double(b: &mut Bencher) {
+fn bench_field_inv(b: &mut Bencher) {
+    let mod257 = gf2m::compute_modulus(257, 12, 0, 0);
+    let value_a = gf2m::parse_hex(b"aff3ee09cb429284985849e20de5742e194aa631490f62ba88702505629a65890");
+
+    b.iter(|| {
+        gf2m::inv(&value_a, &mod257);
+    });
+}
+
+#[bench]
+fn bench_field_batch_invert(b: &mut Bencher) {
     let mod257 = gf2m::compute_modulus(257, 12, 0, 0);
-    let curve_a = gf2m::zero();
+    let value_a = gf2m::parse_hex(b"aff3ee09cb429284985849e20de5742e194aa631490f62ba88702505629a65890");
+    let mut values = vec![value_a; 16];
+
+    b.iter(|| {
+        let _ = gf2m::batch_invert(&mut values, &mod257);
+    });
+}
+
+#[bench]
+fn bench_point_double(b: &mut Bencher) {
+    let curve = dstu_params::curve_257();
     let point = curve::Point {
-        x: gf2m::parse_hex(b"00AFF3EE09CB429284985849E20DE5742E194AA631490F62BA88702505629A6589"),
-        y: gf2m::parse_hex(b"01B345BC134F27DA251EDFAE97B3F306B4E8B8CB9CF86D8651E4FB301EF8E1239C")
+        x: gf2m::parse_hex_words(b"00AFF3EE09CB429284985849E20DE5742E194AA631490F62BA88702505629A6589"),
+        y: gf2m::parse_hex_words(b"01B345BC134F27DA251EDFAE97B3F306B4E8B8CB9CF86D8651E4FB301EF8E1239C")
     };
 
     b.iter(|| {
-        curve::point_dbl(&point, &mod257, &curve_a);
+        curve::point_dbl(&point, &curve);
     });
 }
 
 #[bench]
 fn bench_point_add(b: &mut Bencher) {
-    let mod257 = gf2m::compute_modulus(257, 12, 0, 0);
-    let curve_a = gf2m::zero();
+    let curve = dstu_params::curve_257();
 
     let point = curve::Point {
-        x: gf2m::parse_hex(b"00AFF3EE09CB429284985849E20DE5742E194AA631490F62BA88702505629A6589"),
-        y: gf2m::parse_hex(b"01B345BC134F27DA251EDFAE97B3F306B4E8B8CB9CF86D8651E4FB301EF8E1239C")
+        x: gf2m::parse_hex_words(b"00AFF3EE09CB429284985849E20DE5742E194AA631490F62BA88702505629A6589"),
+        y: gf2m::parse_hex_words(b"01B345BC134F27DA251EDFAE97B3F306B4E8B8CB9CF86D8651E4FB301EF8E1239C")
     };
 
     let point_2 = curve::Point {
-        x: gf2m::parse_hex(b"176dbde19773dfd335665597e8d6a0ab678721a5bb7030f25dc4c48b809ef3520"),
-        y: gf2m::parse_hex(b"6e75301556ea5d571403086691030f024c026907c8e818b2eedd9184d12040ee")
+        x: gf2m::parse_hex_words(b"176dbde19773dfd335665597e8d6a0ab678721a5bb7030f25dc4c48b809ef3520"),
+        y: gf2m::parse_hex_words(b"6e75301556ea5d571403086691030f024c026907c8e818b2eedd9184d12040ee")
     };
 
 
     b.iter(|| {
-        curve::point_add(&point, &point_2, &mod257, &curve_a);
+        curve::point_add(&point, &point_2, &curve);
     });
 }
 
 
 #[bench]
 fn bench_point_add_same(b: &mut Bencher) {
-    let mod257 = gf2m::compute_modulus(257, 12, 0, 0);
-    let curve_a = gf2m::zero();
+    let curve = dstu_params::curve_257();
 
     let point = curve::Point {
-        x: gf2m::parse_hex(b"00AFF3EE09CB429284985849E20DE5742E194AA631490F62BA88702505629A6589"),
-        y: gf2m::parse_hex(b"01B345BC134F27DA251EDFAE97B3F306B4E8B8CB9CF86D8651E4FB301EF8E1239C")
+        x: gf2m::parse_hex_words(b"00AFF3EE09CB429284985849E20DE5742E194AA631490F62BA88702505629A6589"),
+        y: gf2m::parse_hex_words(b"01B345BC134F27DA251EDFAE97B3F306B4E8B8CB9CF86D8651E4FB301EF8E1239C")
     };
 
     b.iter(|| {
-        curve::point_add(&point, &point, &mod257, &curve_a);
+        curve::point_add(&point, &point, &curve);
     });
 }
 
 
 #[bench]
 fn bench_point_mul(b: &mut Bencher) {
-    let mod257 = gf2m::compute_modulus(257, 12, 0, 0);
-    let curve_a = gf2m::zero();
-    let privd = gf2m::parse_hex(b"2A45EAFE4CD469F811737780C57253360FBCC58E134C9A1FDCD10B0E4529A143");
+    let curve = dstu_params::curve_257();
+    let privd = gf2m::parse_hex_words(b"2A45EAFE4CD469F811737780C57253360FBCC58E134C9A1FDCD10B0E4529A143");
 
     let point = curve::Point {
-        x: gf2m::parse_hex(b"00AFF3EE09CB429284985849E20DE5742E194AA631490F62BA88702505629A6589"),
-        y: gf2m::parse_hex(b"01B345BC134F27DA251EDFAE97B3F306B4E8B8CB9CF86D8651E4FB301EF8E1239C")
+        x: gf2m::parse_hex_words(b"00AFF3EE09CB429284985849E20DE5742E194AA631490F62BA88702505629A6589"),
+        y: gf2m::parse_hex_words(b"01B345BC134F27DA251EDFAE97B3F306B4E8B8CB9CF86D8651E4FB301EF8E1239C")
     };
 
     b.iter(|| {
-        curve::point_mul(&point, &privd, &mod257, &curve_a);
+        curve::point_mul(&point, &privd, &curve);
     });
 }
 
@@ -156,13 +301,13 @@ fn bench_point_mul(b: &mut Bencher) {
 fn bench_verify_sign(b: &mut Bencher) {
     let curve = dstu_params::curve_257();
 
-    let s = gf2m::parse_hex(b"0CCC6816453A903A1B641DF999011177DF420D21A72236D798532AEF42E224AB");
-    let r = gf2m::parse_hex(b"491FA1EF75EAEF75E1F20CF3918993AB37E06005EA8E204BC009A1FA61BB0FB2");
-    let to_be_signed = gf2m::parse_hex(b"6845214B63288A832A772E1FE6CB6C7D3528569E29A8B3584370FDC65F474242");
+    let s = gf2m::parse_hex_words(b"0CCC6816453A903A1B641DF999011177DF420D21A72236D798532AEF42E224AB");
+    let r = gf2m::parse_hex_words(b"491FA1EF75EAEF75E1F20CF3918993AB37E06005EA8E204BC009A1FA61BB0FB2");
+    let to_be_signed = gf2m::parse_hex_words(b"6845214B63288A832A772E1FE6CB6C7D3528569E29A8B3584370FDC65F474242");
 
     let pubkey = curve::Point {
-        x: gf2m::parse_hex(b"aff3ee09cb429284985849e20de5742e194aa631490f62ba88702505629a6589"),
-        y: gf2m::parse_hex(b"1b345bc134f27da251edfae97b3f306b4e8b8cb9cf86d8651e4fb301ef8e1239c")
+        x: gf2m::parse_hex_words(b"aff3ee09cb429284985849e20de5742e194aa631490f62ba88702505629a6589"),
+        y: gf2m::parse_hex_words(b"1b345bc134f27da251edfae97b3f306b4e8b8cb9cf86d8651e4fb301ef8e1239c")
     };
 
     b.iter(|| {
@@ -173,3 +318,135 @@ fn bench_verify_sign(b: &mut Bencher) {
         );
     });
 }
+
+#[bench]
+fn bench_field_trace(b: &mut Bencher) {
+    let field = BinaryField::new(431, 5, 3, 1).unwrap();
+    let value = gf2m::parse_hex(b"44d59ab11eebf43534911d05992fc24fa8c6d0da68276eaf1bcde5dc6767705b527b007e700da85f1dc5f550392cabca624bbdf91bb7");
+
+    b.iter(|| {
+        field.trace(&value);
+    });
+}
+
+#[bench]
+fn bench_point_expand(b: &mut Bencher) {
+    let curve = dstu_params::curve_431();
+    let compressed = gf2m::parse_hex_words(b"44d59ab11eebf43534911d05992fc24fa8c6d0da68276eaf1bcde5dc6767705b527b007e700da85f1dc5f550392cabca624bbdf91bb6");
+
+    b.iter(|| {
+        curve::point_expand(&compressed, &curve).unwrap();
+    });
+}
+
+#[bench]
+fn bench_onb_mul_431(b: &mut Bencher) {
+    let field: OnbField = OnbField::new(431).unwrap();
+    let value_a = gf2m::parse_hex(b"44d59ab11eebf43534911d05992fc24fa8c6d0da68276eaf1bcde5dc6767705b527b007e700da85f1dc5f550392cabca624bbdf91bb7");
+    let value_b = gf2m::parse_hex(b"6edb5b3e38bf271233378ac0fe3990289007928f56beb38a4f63843b9995afdd88a09c7da6935a4b43b0afde65a4ca9c159d72ed5275");
+
+    b.iter(|| {
+        field.mul(&value_a, &value_b);
+    });
+}
+
+#[bench]
+fn bench_field_mul_prepared(b: &mut Bencher) {
+    let value_a = gf2m::parse_hex(b"aff3ee09cb429284985849e20de5742e194aa631490f62ba88702505629a65890");
+    let prepared = gf2m::PreparedMultiplicand::new(&gf2m::parse_hex(b"a3391f6f341d627ab958fc4223ee8871e336c8d9dda30f407c369268363f0cccb"));
+
+    b.iter(|| {
+        prepared.mul_comb(&value_a);
+    });
+}
+
+#[bench]
+fn bench_field_mul_prepared_clmul(b: &mut Bencher) {
+    let value_a = gf2m::parse_hex(b"aff3ee09cb429284985849e20de5742e194aa631490f62ba88702505629a65890");
+    let prepared = gf2m::PreparedMultiplicand::new(&gf2m::parse_hex(b"a3391f6f341d627ab958fc4223ee8871e336c8d9dda30f407c369268363f0cccb"));
+
+    b.iter(|| {
+        prepared.mul(&value_a);
+    });
+}
+
+/* Preparing costs no table when mul takes the CLMUL path. */
+#[bench]
+fn bench_field_prepare_mul(b: &mut Bencher) {
+    let value_a = gf2m::parse_hex(b"aff3ee09cb429284985849e20de5742e194aa631490f62ba88702505629a65890");
+    let value_b = gf2m::parse_hex(b"a3391f6f341d627ab958fc4223ee8871e336c8d9dda30f407c369268363f0cccb");
+
+    b.iter(|| {
+        gf2m::PreparedMultiplicand::new(&value_b).mul(&value_a);
+    });
+}
+
+#[bench]
+fn bench_point_mul_base_431(b: &mut Bencher) {
+    let curve = dstu_params::curve_431();
+    let privd = gf2m::parse_hex_words(b"2A45EAFE4CD469F811737780C57253360FBCC58E134C9A1FDCD10B0E4529A143");
+
+    b.iter(|| {
+        curve::point_mul_base(&privd, &curve);
+    });
+}
+
+#[bench]
+fn bench_point_mul_base(b: &mut Bencher) {
+    let curve = dstu_params::curve_257();
+    let privd = gf2m::parse_hex_words(b"2A45EAFE4CD469F811737780C57253360FBCC58E134C9A1FDCD10B0E4529A143");
+
+    b.iter(|| {
+        curve::point_mul_base(&privd, &curve);
+    });
+}
+
+fn batch_inputs<const N: usize>(curve: &curve::Curve<N>) -> (Vec<curve::Point<N>>, Vec<[u32; N]>) {
+    let privd = gf2m::parse_hex_words(b"2A45EAFE4CD469F811737780C57253360FBCC58E134C9A1FDCD10B0E4529A143");
+    let mut points = Vec::new();
+    let mut factors = Vec::new();
+    for i in 0..bitslice::LANES {
+        let mut factor = privd;
+        factor[0] ^= i as u32;
+        points.push(curve::point_mul_base(&factor, curve));
+        factor[1] ^= i as u32;
+        factors.push(factor);
+    }
+    return (points, factors);
+}
+
+#[bench]
+fn bench_point_mul_loop_64(b: &mut Bencher) {
+    let curve = dstu_params::curve_257();
+    let (points, factors) = batch_inputs(&curve);
+
+    b.iter(|| {
+        for i in 0..points.len() {
+            curve::point_mul(&points[i], &factors[i], &curve);
+        }
+    });
+}
+
+#[bench]
+fn bench_point_mul_batch_64(b: &mut Bencher) {
+    let curve = dstu_params::curve_257();
+    let (points, factors) = batch_inputs(&curve);
+
+    b.iter(|| {
+        bitslice::point_mul_batch(&points, &factors, &curve);
+    });
+}
+
+#[bench]
+fn bench_batch_field_mul(b: &mut Bencher) {
+    let field: BinaryField = BinaryField::new(257, 12, 0, 0).unwrap();
+    let batch = bitslice::BatchField::from_field(&field);
+    let value_a = gf2m::parse_hex(b"aff3ee09cb429284985849e20de5742e194aa631490f62ba88702505629a65890");
+    let value_b = gf2m::parse_hex(b"a3391f6f341d627ab958fc4223ee8871e336c8d9dda30f407c369268363f0cccb");
+    let sliced_a = batch.pack(&vec![value_a; bitslice::LANES]);
+    let sliced_b = batch.pack(&vec![value_b; bitslice::LANES]);
+
+    b.iter(|| {
+        batch.mul(&sliced_a, &sliced_b);
+    });
+}
@@ -0,0 +1,308 @@
+/* Bitsliced field arithmetic: word i holds bit i of LANES independent
+   elements of the same field, lane l in bit l. Every operation works
+   on all lanes at once with plain AND/XOR and no branches on data,
+   which pays off for batches of unrelated operations on one curve,
+   such as verifying many signatures. */
+
+use curve::{Curve, Point};
+use field::BinaryField;
+use gf2m;
+
+pub const LANES: usize = 64;
+
+/* m words, bit i of every lane. */
+pub type Sliced = Vec<u64>;
+
+pub struct BatchField {
+    field_m: usize,
+    // nonzero exponents of the modulus below m, always ends with 0
+    terms: Vec<usize>,
+}
+
+impl BatchField {
+    pub fn new(field_m: usize, k1: usize, k2: usize, k3: usize) -> BatchField {
+        let mut terms = Vec::new();
+        for &k in [k1, k2, k3].iter() {
+            if k > 0 {
+                terms.push(k);
+            }
+        }
+        terms.push(0);
+        return BatchField {
+            field_m: field_m,
+            terms: terms,
+        };
+    }
+
+    pub fn from_field<const N: usize>(field: &BinaryField<N>) -> BatchField {
+        let (field_m, k1, k2, k3) = field.terms();
+        return BatchField::new(field_m, k1, k2, k3);
+    }
+
+    pub fn degree(&self) -> usize {
+        return self.field_m;
+    }
+
+    pub fn zero(&self) -> Sliced {
+        return vec![0; self.field_m];
+    }
+
+    /* Same value in every lane. */
+    pub fn broadcast(&self, value: &[u32]) -> Sliced {
+        let mut ret = self.zero();
+        for i in 0..self.field_m {
+            if gf2m::has_bit(value, i) {
+                ret[i] = !0;
+            }
+        }
+        return ret;
+    }
+
+    pub fn one(&self) -> Sliced {
+        let mut ret = self.zero();
+        ret[0] = !0;
+        return ret;
+    }
+
+    /* Up to LANES values, missing lanes are zero. */
+    pub fn pack<const N: usize>(&self, values: &[[u32; N]]) -> Sliced {
+        assert!(values.len() <= LANES);
+        let mut ret = self.zero();
+        for (lane, value) in values.iter().enumerate() {
+            for i in 0..self.field_m {
+                if gf2m::has_bit(value, i) {
+                    ret[i] |= 1 << lane;
+                }
+            }
+        }
+        return ret;
+    }
+
+    /* First count lanes as separate values. */
+    pub fn unpack<const N: usize>(&self, value: &Sliced, count: usize) -> Vec<[u32; N]> {
+        assert!(count <= LANES);
+        let mut ret = vec![[0; N]; count];
+        for i in 0..self.field_m {
+            for lane in 0..count {
+                ret[lane][i / 32] |= (((value[i] >> lane) & 1) as u32) << (i % 32);
+            }
+        }
+        return ret;
+    }
+
+    pub fn add(&self, value_a: &Sliced, value_b: &Sliced) -> Sliced {
+        let mut ret = value_a.clone();
+        for i in 0..self.field_m {
+            ret[i] ^= value_b[i];
+        }
+        return ret;
+    }
+
+    /* Takes 2m - 1 words, folds x^m = sum of x^k from the top down. */
+    fn reduce(&self, mut value: Vec<u64>) -> Sliced {
+        let field_m = self.field_m;
+        let mut i = value.len();
+        while i > field_m {
+            i -= 1;
+            let top = value[i];
+            for &k in self.terms.iter() {
+                value[i - field_m + k] ^= top;
+            }
+        }
+        value.truncate(field_m);
+        return value;
+    }
+
+    /* Schoolbook, m^2 word ANDs. */
+    pub fn mul(&self, value_a: &Sliced, value_b: &Sliced) -> Sliced {
+        let field_m = self.field_m;
+        let mut product = vec![0u64; field_m * 2 - 1];
+        for i in 0..field_m {
+            let word = value_a[i];
+            let row = &mut product[i..i + field_m];
+            for j in 0..field_m {
+                row[j] ^= word & value_b[j];
+            }
+        }
+        return self.reduce(product);
+    }
+
+    /* Multiplies every lane by the same constant, one XOR pass per
+       set bit of it. */
+    pub fn mul_const(&self, value: &Sliced, constant: &[u32]) -> Sliced {
+        let field_m = self.field_m;
+        let mut product = vec![0u64; field_m * 2 - 1];
+        for j in 0..field_m {
+            if !gf2m::has_bit(constant, j) {
+                continue;
+            }
+            let row = &mut product[j..j + field_m];
+            for i in 0..field_m {
+                row[i] ^= value[i];
+            }
+        }
+        return self.reduce(product);
+    }
+
+    /* Squaring only spreads bits out. */
+    pub fn sqr(&self, value: &Sliced) -> Sliced {
+        let mut product = vec![0u64; self.field_m * 2 - 1];
+        for i in 0..self.field_m {
+            product[i * 2] = value[i];
+        }
+        return self.reduce(product);
+    }
+
+    fn sqr_times(&self, value: &Sliced, count: usize) -> Sliced {
+        let mut ret = value.clone();
+        for _ in 0..count {
+            ret = self.sqr(&ret);
+        }
+        return ret;
+    }
+
+    /* Itoh-Tsujii, value^(2^m - 2). Lanes holding zero stay zero. */
+    pub fn inv(&self, value: &Sliced) -> Sliced {
+        let chain = self.field_m - 1;
+        let mut bit = (usize::BITS - chain.leading_zeros()) as usize - 1;
+        // beta = value^(2^k - 1)
+        let mut beta = value.clone();
+        let mut k = 1;
+
+        while bit > 0 {
+            bit -= 1;
+            beta = self.mul(&self.sqr_times(&beta, k), &beta);
+            k *= 2;
+
+            if (chain >> bit) & 1 == 1 {
+                beta = self.mul(&self.sqr(&beta), value);
+                k += 1;
+            }
+        }
+
+        return self.sqr(&beta);
+    }
+
+    /* Lanes with the mask bit set take value_b, others value_a. */
+    pub fn select(&self, mask: u64, value_a: &Sliced, value_b: &Sliced) -> Sliced {
+        let mut ret = self.zero();
+        for i in 0..self.field_m {
+            ret[i] = value_a[i] ^ ((value_a[i] ^ value_b[i]) & mask);
+        }
+        return ret;
+    }
+
+    /* Mask of lanes holding zero. */
+    pub fn is_zero(&self, value: &Sliced) -> u64 {
+        let mut acc = 0;
+        for i in 0..self.field_m {
+            acc |= value[i];
+        }
+        return !acc;
+    }
+}
+
+/* Mask of lanes whose factor has the bit set. */
+fn bit_mask<const N: usize>(factors: &[[u32; N]], bit: usize) -> u64 {
+    let mut ret = 0;
+    for (lane, factor) in factors.iter().enumerate() {
+        ret |= (((factor[bit / 32] >> (bit % 32)) & 1) as u64) << lane;
+    }
+    return ret;
+}
+
+/* Ref: Lopez, Dahab. Fast multiplication on elliptic curves over
+   GF(2^m) without precomputation, 1999.
+
+   x-only Montgomery ladder on (X1 : Z1) = kP, (X2 : Z2) = (k + 1)P,
+   started from infinity so lanes do not need the top bit set.
+   Both branches of a ladder step run for every lane, the factor
+   bit only decides which pair is swapped in. Loop count depends
+   only on the longest factor in the chunk. */
+fn ladder_chunk<const N: usize>(points: &[Point<N>], factors: &[[u32; N]], field: &BatchField,
+                                curve: &Curve<N>) -> Vec<Point<N>> {
+    let count = points.len();
+    let xs: Vec<[u32; N]> = points.iter().map(|point| point.x).collect();
+    let ys: Vec<[u32; N]> = points.iter().map(|point| point.y).collect();
+    let x = field.pack(&xs);
+    let y = field.pack(&ys);
+
+    let mut x1 = field.one();
+    let mut z1 = field.zero();
+    let mut x2 = x.clone();
+    let mut z2 = field.one();
+
+    let mut bit = factors.iter().map(|factor| gf2m::bit_size(factor)).max().unwrap_or(0);
+    while bit > 0 {
+        bit -= 1;
+        // swap where the bit is clear, then P1 = P1 + P2, P2 = 2 P2
+        let swap = !bit_mask(factors, bit);
+        let (sx1, sx2) = (field.select(swap, &x1, &x2), field.select(swap, &x2, &x1));
+        let (sz1, sz2) = (field.select(swap, &z1, &z2), field.select(swap, &z2, &z1));
+
+        // Madd: Z = (X1 Z2 + X2 Z1)^2, X = x Z + X1 Z2 X2 Z1
+        let t1 = field.mul(&sx1, &sz2);
+        let t2 = field.mul(&sx2, &sz1);
+        let az = field.sqr(&field.add(&t1, &t2));
+        let ax = field.add(&field.mul(&x, &az), &field.mul(&t1, &t2));
+
+        // Mdouble: X = X^4 + b Z^4, Z = X^2 Z^2
+        let xx = field.sqr(&sx2);
+        let zz = field.sqr(&sz2);
+        let dx = field.add(&field.sqr(&xx), &field.mul_const(&field.sqr(&zz), curve.param_b()));
+        let dz = field.mul(&xx, &zz);
+
+        x1 = field.select(swap, &ax, &dx);
+        x2 = field.select(swap, &dx, &ax);
+        z1 = field.select(swap, &az, &dz);
+        z2 = field.select(swap, &dz, &az);
+    }
+
+    // Mxy: y(kP) = (x + x1)((X1 + x Z1)(X2 + x Z2) + (x^2 + y) Z1 Z2)
+    //              / (x Z1 Z2) + y,  one inversion for all lanes
+    let z1z2 = field.mul(&z1, &z2);
+    let inv = field.inv(&field.mul(&x, &z1z2));
+    let rx = field.mul(&field.mul(&x1, &field.mul(&x, &z2)), &inv);
+
+    let u1 = field.add(&x1, &field.mul(&x, &z1));
+    let u2 = field.add(&x2, &field.mul(&x, &z2));
+    let v = field.add(&field.mul(&u1, &u2), &field.mul(&field.add(&field.sqr(&x), &y), &z1z2));
+    let ry = field.add(&field.mul(&field.mul(&field.add(&x, &rx), &v), &inv), &y);
+
+    // kP = -P = (x, x + y) when (k + 1)P is infinity
+    let neg = field.is_zero(&z2);
+    let rx = field.select(neg, &rx, &x);
+    let ry = field.select(neg, &ry, &field.add(&x, &y));
+
+    // points with x = 0 have order 2 (or are infinity)
+    let low = bit_mask(factors, 0);
+    let order2 = field.is_zero(&x);
+    let zero = field.zero();
+    let rx = field.select(order2, &rx, &field.select(low, &zero, &x));
+    let ry = field.select(order2, &ry, &field.select(low, &zero, &y));
+
+    // kP is infinity
+    let inf = field.is_zero(&z1) & !order2;
+    let rx = field.select(inf, &rx, &zero);
+    let ry = field.select(inf, &ry, &zero);
+
+    let rxs = field.unpack(&rx, count);
+    let rys = field.unpack(&ry, count);
+    let mut ret = Vec::with_capacity(count);
+    for lane in 0..count {
+        ret.push(Point {x: rxs[lane], y: rys[lane]});
+    }
+    return ret;
+}
+
+/* factors[i] * points[i] for every i, LANES at a time. Same results
+   as curve::point_mul, points are expected on the curve. */
+pub fn point_mul_batch<const N: usize>(points: &[Point<N>], factors: &[[u32; N]], curve: &Curve<N>) -> Vec<Point<N>> {
+    assert_eq!(points.len(), factors.len());
+    let field = BatchField::from_field(curve.field());
+    let mut ret = Vec::with_capacity(points.len());
+    for (points, factors) in points.chunks(LANES).zip(factors.chunks(LANES)) {
+        ret.extend(ladder_chunk(points, factors, &field, curve));
+    }
+    return ret;
+}
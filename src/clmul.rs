@@ -0,0 +1,180 @@
+/* Field multiplication on top of the carry-less multiply instruction
+   (PCLMULQDQ). Support is detected at runtime, callers get None when
+   the CPU (or target architecture) does not have it and should fall
+   back to the portable gf2m::mul. */
+
+use gf2m;
+
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+#[cfg(target_arch = "x86_64")]
+pub fn is_available() -> bool {
+    return is_x86_feature_detected!("pclmulqdq") && is_x86_feature_detected!("sse2");
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+pub fn is_available() -> bool {
+    return false;
+}
+
+#[cfg(target_arch = "x86_64")]
+#[inline]
+#[target_feature(enable = "pclmulqdq,sse2")]
+unsafe fn mul_1x1_u64(a: u64, b: u64) -> (u64, u64) {
+    let r = _mm_clmulepi64_si128(
+        _mm_set_epi64x(0, a as i64),
+        _mm_set_epi64x(0, b as i64),
+        0x00
+    );
+    let l = _mm_cvtsi128_si64(r) as u64;
+    let h = _mm_cvtsi128_si64(_mm_unpackhi_epi64(r, r)) as u64;
+    return (l, h);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[inline]
+#[target_feature(enable = "pclmulqdq,sse2")]
+unsafe fn mul_2x2_u64(a1: u64, a0: u64, b1: u64, b0: u64) -> (u64, u64, u64, u64) {
+    let (z0l, z0h) = mul_1x1_u64(a0, b0);
+    let (z1l, z1h) = mul_1x1_u64(a1, b1);
+    let (z2l, z2h) = mul_1x1_u64(a0 ^ a1, b0 ^ b1);
+
+    let z1l = z1l ^ z0h ^ z1h ^ z2h;
+    let z0h = z1h ^ z1l ^ z0l ^ z2l ^ z2h;
+    return (z0l, z0h, z1l, z1h);
+}
+
+/* Widest operand supported, in 64-bit limbs. */
+const MAX_LIMBS: usize = 16;
+
+fn to_limbs(value: &[u32]) -> [u64; MAX_LIMBS] {
+    assert!(value.len() <= MAX_LIMBS * 2);
+    let mut ret = [0; MAX_LIMBS];
+    for i in 0..value.len() {
+        ret[i >> 1] |= (value[i] as u64) << ((i % 2) * 32);
+    }
+    return ret;
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "pclmulqdq,sse2")]
+unsafe fn mul_limbs(value_a: &[u64; MAX_LIMBS], value_b: &[u64; MAX_LIMBS],
+                    size: usize) -> [u64; MAX_LIMBS * 2] {
+    let mut result = [0; MAX_LIMBS * 2];
+
+    let mut j = 0;
+    while j < size {
+        let y0 = value_b[j];
+        let y1 = value_b[j + 1];
+
+        let mut i = 0;
+        while i < size {
+            let (l0, h0, l1, h1) = mul_2x2_u64(value_a[i + 1], value_a[i], y1, y0);
+            result[j + i] ^= l0;
+            result[j + i + 1] ^= h0;
+            result[j + i + 2] ^= l1;
+            result[j + i + 3] ^= h1;
+            i += 2;
+        }
+        j += 2;
+    }
+    return result;
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "pclmulqdq,sse2")]
+unsafe fn sqr_limbs(value: &[u64; MAX_LIMBS], size: usize) -> [u64; MAX_LIMBS * 2] {
+    let mut result = [0; MAX_LIMBS * 2];
+    for i in 0..size {
+        let (l, h) = mul_1x1_u64(value[i], value[i]);
+        result[i * 2] = l;
+        result[i * 2 + 1] = h;
+    }
+    return result;
+}
+
+/* out = a * b for slices of the same length, up to 1024 bits,
+   out has twice that length. False when the instruction is not
+   available, out is left alone then. */
+#[cfg(target_arch = "x86_64")]
+pub fn mul_slices(value_a: &[u32], value_b: &[u32], out: &mut [u32]) -> bool {
+    if !is_available() {
+        return false;
+    }
+    assert!(value_a.len() == value_b.len() && out.len() == value_a.len() * 2);
+    // limbs are taken in pairs
+    let size = value_a.len().div_ceil(4) * 2;
+    let result = unsafe {
+        mul_limbs(&to_limbs(value_a), &to_limbs(value_b), size)
+    };
+    for i in 0..out.len() {
+        out[i] = (result[i >> 1] >> ((i % 2) * 32)) as u32;
+    }
+    return true;
+}
+
+/* out = value^2, same limits as mul_slices. */
+#[cfg(target_arch = "x86_64")]
+pub fn sqr_slices(value: &[u32], out: &mut [u32]) -> bool {
+    if !is_available() {
+        return false;
+    }
+    assert!(out.len() == value.len() * 2);
+    let size = value.len().div_ceil(2);
+    let result = unsafe { sqr_limbs(&to_limbs(value), size) };
+    for i in 0..out.len() {
+        out[i] = (result[i >> 1] >> ((i % 2) * 32)) as u32;
+    }
+    return true;
+}
+
+/* Product of two N-word values as W = 2 * N words,
+   up to 1024-bit operands. */
+#[cfg(target_arch = "x86_64")]
+pub fn mul_words<const N: usize, const W: usize>(value_a: &[u32; N], value_b: &[u32; N]) -> Option<[u32; W]> {
+    assert!(W == N * 2);
+    let mut ret = [0; W];
+    if !mul_slices(value_a, value_b, &mut ret) {
+        return None;
+    }
+    return Some(ret);
+}
+
+#[cfg(target_arch = "x86_64")]
+pub fn sqr_words<const N: usize, const W: usize>(value: &[u32; N]) -> Option<[u32; W]> {
+    assert!(W == N * 2);
+    let mut ret = [0; W];
+    if !sqr_slices(value, &mut ret) {
+        return None;
+    }
+    return Some(ret);
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+pub fn mul_slices(_value_a: &[u32], _value_b: &[u32], _out: &mut [u32]) -> bool {
+    return false;
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+pub fn sqr_slices(_value: &[u32], _out: &mut [u32]) -> bool {
+    return false;
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+pub fn mul_words<const N: usize, const W: usize>(_value_a: &[u32; N], _value_b: &[u32; N]) -> Option<[u32; W]> {
+    return None;
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+pub fn sqr_words<const N: usize, const W: usize>(_value: &[u32; N]) -> Option<[u32; W]> {
+    return None;
+}
+
+pub fn mul(value_a: &gf2m::Field, value_b: &gf2m::Field) -> Option<gf2m::FieldMul> {
+    return mul_words(value_a, value_b);
+}
+
+pub fn sqr(value: &gf2m::Field) -> Option<gf2m::FieldMul> {
+    return sqr_words(value);
+}
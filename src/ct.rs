@@ -0,0 +1,247 @@
+/* Constant-time variants of the gf2m routines that see secret values
+   (private keys, nonces and values derived from them).
+
+   Running time and memory access pattern of everything here depends
+   only on public parameters: the modulus and lengths of the inputs.
+   No branches or table indexes are derived from element values:
+   in constant-time builds the word multiplications of gf2m and
+   gf2m64 and the squaring bit spread use masks instead of their
+   window and spread tables.
+   Remaining caveats:
+    - PreparedMultiplicand::mul_comb indexes its table by nibbles of
+      the other operand, constant-time builds only reach it through
+      verify and point_expand, on public values;
+    - only curve::point_mul_ct is safe for secret factors, affine
+      point_add and point_dbl branch on infinity and equal x, and
+      point_mul_base on factor bits outside constant-time builds.
+
+   Build with `--features constant-time` to route gf2m::reduce, neg,
+   truncate, is_zero and curve::point_mul through this module. */
+
+use std::cmp::Ordering;
+
+use gf2m;
+
+const WORD_SIZE: usize = 32;
+
+/* All ones when bit is set, zero otherwise. */
+#[inline]
+fn mask(bit: u32) -> u32 {
+    return 0u32.wrapping_sub(bit & 1);
+}
+
+/* 1 when word is zero, 0 otherwise. */
+#[inline]
+fn word_is_zero(word: u32) -> u32 {
+    return ((word | word.wrapping_neg()) >> 31) ^ 1;
+}
+
+pub fn is_zero<const N: usize>(value: &[u32; N]) -> bool {
+    let mut acc = 0;
+    for i in 0..N {
+        acc |= value[i];
+    }
+    return word_is_zero(acc) == 1;
+}
+
+pub fn eq<const N: usize>(value_a: &[u32; N], value_b: &[u32; N]) -> bool {
+    let mut acc = 0;
+    for i in 0..N {
+        acc |= value_a[i] ^ value_b[i];
+    }
+    return word_is_zero(acc) == 1;
+}
+
+/* Returns value_b when choice is 1 and value_a when it is 0. */
+pub fn select<const N: usize>(value_a: &[u32; N], value_b: &[u32; N], choice: u32) -> [u32; N] {
+    let choice = mask(choice);
+    let mut ret = [0; N];
+    for i in 0..N {
+        ret[i] = value_a[i] ^ (choice & (value_a[i] ^ value_b[i]));
+    }
+    return ret;
+}
+
+/* Swaps two values when choice is 1. */
+pub fn cswap<const N: usize>(value_a: &mut [u32; N], value_b: &mut [u32; N], choice: u32) {
+    let choice = mask(choice);
+    for i in 0..N {
+        let t = choice & (value_a[i] ^ value_b[i]);
+        value_a[i] ^= t;
+        value_b[i] ^= t;
+    }
+}
+
+/* 1 when value_a < value_b as multiword integers, that is when
+   value_a - value_b borrows out of the top word. */
+#[inline]
+fn lt_bit<const N: usize>(value_a: &[u32; N], value_b: &[u32; N]) -> u32 {
+    let mut borrow = 0u64;
+    for i in 0..N {
+        let diff = (value_a[i] as u64).wrapping_sub(value_b[i] as u64).wrapping_sub(borrow);
+        borrow = diff >> 63;
+    }
+    return borrow as u32;
+}
+
+/* value_a + value_b as multiword integers and the carry out
+   of the top word. */
+pub fn int_add<const N: usize>(value_a: &[u32; N], value_b: &[u32; N]) -> ([u32; N], u32) {
+    let mut ret = [0; N];
+    let mut carry = 0u64;
+    for i in 0..N {
+        let sum = value_a[i] as u64 + value_b[i] as u64 + carry;
+        ret[i] = sum as u32;
+        carry = sum >> 32;
+    }
+    return (ret, carry as u32);
+}
+
+/* value_a - value_b as multiword integers and the borrow out
+   of the top word. */
+pub fn int_sub<const N: usize>(value_a: &[u32; N], value_b: &[u32; N]) -> ([u32; N], u32) {
+    let mut ret = [0; N];
+    let mut borrow = 0u64;
+    for i in 0..N {
+        let diff = (value_a[i] as u64).wrapping_sub(value_b[i] as u64 + borrow);
+        ret[i] = diff as u32;
+        borrow = diff >> 63;
+    }
+    return (ret, borrow as u32);
+}
+
+/* value mod order by shift-and-subtract. The number of steps depends
+   only on the bit size of order, each subtraction is kept by mask.
+   order must not be zero. */
+pub fn int_reduce<const N: usize>(value: &[u32; N], order: &[u32; N]) -> [u32; N] {
+    let mut ret = *value;
+    let mut shift = N * WORD_SIZE - gf2m::bit_size(order) + 1;
+    while shift > 0 {
+        shift -= 1;
+        let (diff, borrow) = int_sub(&ret, &gf2m::shl_words(order, shift));
+        ret = select(&diff, &ret, borrow);
+    }
+    return ret;
+}
+
+/* Integer ordering of two values, only the result is branched on. */
+pub fn cmp<const N: usize>(value_a: &[u32; N], value_b: &[u32; N]) -> Ordering {
+    let less = lt_bit(value_a, value_b);
+    let greater = lt_bit(value_b, value_a);
+    return match (less, greater) {
+        (1, _) => Ordering::Less,
+        (_, 1) => Ordering::Greater,
+        _ => Ordering::Equal,
+    };
+}
+
+pub fn lt<const N: usize>(value_a: &[u32; N], value_b: &[u32; N]) -> bool {
+    return lt_bit(value_a, value_b) == 1;
+}
+
+pub fn gt<const N: usize>(value_a: &[u32; N], value_b: &[u32; N]) -> bool {
+    return lt_bit(value_b, value_a) == 1;
+}
+
+/* 0 < value < order, range of private keys, nonces and
+   signature components. */
+pub fn in_range<const N: usize>(value: &[u32; N], order: &[u32; N]) -> bool {
+    let mut acc = 0;
+    for i in 0..N {
+        acc |= value[i];
+    }
+    return ((word_is_zero(acc) ^ 1) & lt_bit(value, order)) == 1;
+}
+
+/* Same result as gf2m::truncate: clears every bit
+   at or above position size - 1. */
+pub fn truncate<const N: usize>(value: &[u32; N], size: usize) -> [u32; N] {
+    let limit = if size > 0 { size - 1 } else { 0 };
+    let mut ret = [0; N];
+    for i in 0..N {
+        let low = i * WORD_SIZE;
+        let keep = if limit >= low + WORD_SIZE {
+            0xFF_FF_FF_FF
+        } else if limit > low {
+            (1 << (limit - low)) - 1
+        } else {
+            0
+        };
+        ret[i] = value[i] & keep;
+    }
+    return ret;
+}
+
+/* Sparse moduli reduce with fixed word-level folding (see
+   gf2m::reduce_sparse), anything else conditionally subtracts
+   shifted modulus for every bit position above m. */
+pub fn reduce<const N: usize>(value: &[u32], modulus: &[u32; N]) -> [u32; N] {
+    match gf2m::sparse_terms(modulus) {
+        Some((field_m, k1, k2, k3)) => {
+            return gf2m::reduce_sparse_words(value, field_m, k1, k2, k3);
+        },
+        None => {},
+    };
+
+    assert!(value.len() <= N * 2);
+    let mut ret: gf2m::Wide<N> = [[0; N]; 2];
+    ret.as_flattened_mut()[..value.len()].copy_from_slice(value);
+
+    let field_m = gf2m::bit_size(modulus) - 1;
+    let mut bit = value.len() * WORD_SIZE;
+    while bit > field_m {
+        bit -= 1;
+        let ret = ret.as_flattened_mut();
+        let choice = mask(ret[bit / WORD_SIZE] >> (bit % WORD_SIZE));
+        // shift is public, only the subtracted words are masked
+        let shift = bit - field_m;
+        let words = shift / WORD_SIZE;
+        let bits = shift % WORD_SIZE;
+        for i in 0..N {
+            let word = choice & modulus[i];
+            if i + words < N * 2 {
+                ret[i + words] ^= word << bits;
+            }
+            if bits > 0 && i + words + 1 < N * 2 {
+                ret[i + words + 1] ^= word >> (WORD_SIZE - bits);
+            }
+        }
+    }
+
+    return ret[0];
+}
+
+/* Inverse by Itoh-Tsujii chain, as gf2m::inv, on top of
+   constant-time reduction. Inverse of zero is zero. Fields with
+   m below 2 fall back to gf2m::neg_words. */
+pub fn inv<const N: usize>(value: &[u32; N], modulus: &[u32; N]) -> [u32; N] {
+    if gf2m::bit_size(modulus) < 3 {
+        return gf2m::neg_words(value, modulus);
+    }
+
+    let field_m = gf2m::bit_size(modulus) - 1;
+    let value = reduce(value, modulus);
+
+    let chain = field_m - 1;
+    let mut bit = (usize::BITS - chain.leading_zeros()) as usize - 1;
+    let mut beta = value;
+    let mut k = 1;
+
+    while bit > 0 {
+        bit -= 1;
+        let mut temp = beta;
+        for _ in 0..k {
+            temp = reduce(gf2m::sqr_wide(&temp).as_flattened(), modulus);
+        }
+        beta = reduce(gf2m::mul_wide(&temp, &beta).as_flattened(), modulus);
+        k *= 2;
+
+        if (chain >> bit) & 1 == 1 {
+            let square = reduce(gf2m::sqr_wide(&beta).as_flattened(), modulus);
+            beta = reduce(gf2m::mul_wide(&square, &value).as_flattened(), modulus);
+            k += 1;
+        }
+    }
+
+    return reduce(gf2m::sqr_wide(&beta).as_flattened(), modulus);
+}
@@ -1,42 +1,154 @@
+use ct;
+use field::{BinaryField, FieldBackend, FieldElement, Inv, Scope, Square};
 use gf2m;
-use gf2m::Field;
+use gf2m::FIELD_SIZE;
 
-#[derive(Clone, Debug, Hash)]
-pub struct Point {
-    pub x: Field,
-    pub y: Field,
+#[derive(Clone, Debug, Hash, PartialEq)]
+pub struct Point<const N: usize = FIELD_SIZE> {
+    pub x: [u32; N],
+    pub y: [u32; N],
 }
 
-impl PartialEq for Point {
-    #[inline]
-    fn eq(&self, other: &Point) -> bool {
-        return self.x.eq(&other.x) && self.y.eq(&other.y);
+/* Build with Curve::new, which prepares param_b and base for
+   multiplication. Field arithmetic comes from the backend,
+   polynomial basis by default. */
+pub struct Curve<const N: usize = FIELD_SIZE, F: FieldBackend<N> = BinaryField<N>> {
+    pub param_a: [u32; N],
+    pub param_b: [u32; N],
+    pub order: [u32; N],
+    pub base: Point<N>,
+    pub field: F,
+    precomputed: Precomputed<N, F>,
+}
+
+/* Prepared b and base point, with the values they were made from,
+   so that a changed param_b or base is noticed. */
+struct Precomputed<const N: usize, F: FieldBackend<N>> {
+    param_b: [u32; N],
+    base: Point<N>,
+    prepared_b: F::Prepared,
+    prepared_base: PreparedPoint<N, F>,
+}
+
+impl<const N: usize, F: FieldBackend<N>> Precomputed<N, F> {
+    fn new(param_b: &[u32; N], base: &Point<N>, field: &F) -> Precomputed<N, F> {
+        return Precomputed {
+            param_b: *param_b,
+            base: base.clone(),
+            prepared_b: field.prepare(param_b),
+            prepared_base: PreparedPoint::new(base, field),
+        };
     }
 }
 
-pub struct Curve {
-    pub param_a: Field,
-    pub param_b: Field,
-    pub order: Field,
-    pub base: Point,
-    pub field_m: usize,
-    pub field_k1: usize,
-    pub field_k2: usize,
-    pub field_k3: usize,
-    pub modulus: Field,
+/* Affine point as used in mixed additions, x + y is needed too. */
+struct PreparedPoint<const N: usize, F: FieldBackend<N>> {
+    x: F::Prepared,
+    y: F::Prepared,
+    sum: F::Prepared,
 }
 
-pub fn infinity()-> Point {
-    return Point {x: gf2m::zero(), y: gf2m::zero()};
+impl<const N: usize, F: FieldBackend<N>> PreparedPoint<N, F> {
+    fn new(point: &Point<N>, field: &F) -> PreparedPoint<N, F> {
+        return PreparedPoint {
+            x: field.prepare(&point.x),
+            y: field.prepare(&point.y),
+            sum: field.scope(|s| field.prepare((s.element(&point.x) + s.element(&point.y)).value())),
+        };
+    }
 }
 
-pub fn at_infinity(value_x: &Field, value_y: &Field) -> bool {
-    return gf2m::is_zero(value_x) && gf2m::is_zero(value_y);
+impl<const N: usize, F: FieldBackend<N>> Curve<N, F> {
+    pub fn new(param_a: [u32; N], param_b: [u32; N], order: [u32; N], base: Point<N>, field: F) -> Curve<N, F> {
+        let precomputed = Precomputed::new(&param_b, &base, &field);
+        return Curve {
+            param_a: param_a,
+            param_b: param_b,
+            order: order,
+            base: base,
+            field: field,
+            precomputed: precomputed,
+        };
+    }
+
+    /* Runs body with prepared b and base, made again if param_b or
+       base were changed after Curve::new. */
+    fn with_precomputed<R>(&self, body: impl FnOnce(&Precomputed<N, F>) -> R) -> R {
+        if self.precomputed.param_b == self.param_b && self.precomputed.base == self.base {
+            return body(&self.precomputed);
+        }
+        return body(&Precomputed::new(&self.param_b, &self.base, &self.field));
+    }
+
+    pub fn param_a(&self) -> &[u32; N] {
+        return &self.param_a;
+    }
+
+    pub fn param_b(&self) -> &[u32; N] {
+        return &self.param_b;
+    }
+
+    pub fn order(&self) -> &[u32; N] {
+        return &self.order;
+    }
+
+    pub fn base(&self) -> &Point<N> {
+        return &self.base;
+    }
+
+    pub fn field(&self) -> &F {
+        return &self.field;
+    }
+
+    /* Degree of the field polynomial. */
+    pub fn field_m(&self) -> usize {
+        return self.field.degree();
+    }
+
+    /* a is either 0 or 1 for DSTU curves. */
+    fn a_is_one(&self) -> bool {
+        return self.param_a == self.field.unity();
+    }
 }
 
-pub fn point_add(point_a: &Point, point_b: &Point,
-                 modulus: &Field,
-                 curve_a: &Field) -> Point {
+impl<const N: usize> Curve<N, BinaryField<N>> {
+    /* (m, k1, k2, k3) of the field polynomial. */
+    pub fn terms(&self) -> (usize, usize, usize, usize) {
+        return self.field.terms();
+    }
+
+    pub fn modulus(&self) -> &[u32; N] {
+        return self.field.modulus();
+    }
+}
+
+/* Lopez-Dahab projective coordinates: x = X/Z, y = Y/Z^2,
+   Z = 0 is the point at infinity. */
+struct LdPoint<'id, const N: usize, F: FieldBackend<N>> {
+    x: FieldElement<'id, N, F>,
+    y: FieldElement<'id, N, F>,
+    z: FieldElement<'id, N, F>,
+}
+
+impl<'id, const N: usize, F: FieldBackend<N>> LdPoint<'id, N, F> {
+    fn infinity(scope: &Scope<'id, N, F>) -> LdPoint<'id, N, F> {
+        return LdPoint {x: scope.one(), y: scope.zero(), z: scope.zero()};
+    }
+
+    fn affine(point: &Point<N>, scope: &Scope<'id, N, F>) -> LdPoint<'id, N, F> {
+        return LdPoint {x: scope.element(&point.x), y: scope.element(&point.y), z: scope.one()};
+    }
+}
+
+pub fn infinity<const N: usize>()-> Point<N> {
+    return Point {x: [0; N], y: [0; N]};
+}
+
+pub fn at_infinity<const N: usize>(value_x: &[u32; N], value_y: &[u32; N]) -> bool {
+    return gf2m::is_zero_words(value_x) && gf2m::is_zero_words(value_y);
+}
+
+pub fn point_add<const N: usize, F: FieldBackend<N>>(point_a: &Point<N>, point_b: &Point<N>, curve: &Curve<N, F>) -> Point<N> {
 
     if at_infinity(&point_a.x, &point_a.y) {
         return point_b.clone();
@@ -51,133 +163,86 @@ pub fn point_add(point_a: &Point, point_b: &Point,
        X3 = lambda2+lambda+X1+X2+a2
        Y3 = lambda*(X1+X3)+X3+Y1 */
 
-    let lbd;
-    let value_cx;
-
-    if point_a.x.eq(&point_b.x) == false {
-        let neg_abx = gf2m::neg(&gf2m::add(&point_a.x, &point_b.x), modulus);
-        lbd = gf2m::reduce(
-            &gf2m::mul(
-                &gf2m::add(&point_a.y, &point_b.y),
-                &neg_abx,
-            ),
-            modulus
-        );
-        let temp_cx = gf2m::add(
-            curve_a,
-            &gf2m::reduce(&gf2m::mul(&lbd, &lbd), modulus)
-        );
-        let temp_cx = gf2m::add(&temp_cx, &lbd);
-        let temp_cx = gf2m::add(&temp_cx, &point_a.x);
-        value_cx = gf2m::add(&temp_cx, &point_b.x);
-    }
-    else if point_a.y.eq(&point_b.y) == false {
-        return infinity();
-    }
-    else if gf2m::is_zero(&point_a.x) {
-        return infinity();
+    if point_a.x.eq(&point_b.x) {
+        if point_a.y.eq(&point_b.y) == false {
+            return infinity();
+        }
+        return point_dbl(point_a, curve);
     }
-    else {
-        let neg_ax = gf2m::neg(&point_a.x, modulus);
-        lbd = gf2m::add(
-            &point_a.x,
-            &gf2m::reduce(
-                &gf2m::mul(&point_a.y, &neg_ax),
-                modulus
-            )
-        );
-        let temp = gf2m::add(
-            curve_a,
-            &gf2m::reduce(
-                &gf2m::mul(&lbd, &lbd),
-                modulus
-            )
-        );
-        value_cx = gf2m::add(&temp, &lbd);;
-    }
-
-    let value_cy = gf2m::reduce(
-        &gf2m::mul(&gf2m::add(&point_b.x, &value_cx), &lbd),
-        modulus
-    );
-    let value_cy = gf2m::add(&value_cy, &value_cx);
-    let value_cy = gf2m::add(&value_cy, &point_b.y);
-
-    return Point {x: value_cx, y: value_cy};
-}
-
-pub fn point_dbl(point_a: &Point,
-                 modulus: &Field,
-                 curve_a: &Field) -> Point {
+
+    // lambda is a single product, nothing to accumulate
+    return curve.field.scope(|s| {
+        let (x1, y1) = (s.element(&point_a.x), s.element(&point_a.y));
+        let (x2, y2) = (s.element(&point_b.x), s.element(&point_b.y));
+        let lbd = (y1 + y2) * (x1 + x2).inv();
+        let x3 = s.element(&curve.param_a) + lbd.square() + lbd + x1 + x2;
+        let y3 = (x2 + x3) * lbd + x3 + y2;
+
+        return Point {x: *x3.value(), y: *y3.value()};
+    });
+}
+
+pub fn point_dbl<const N: usize, F: FieldBackend<N>>(point_a: &Point<N>, curve: &Curve<N, F>) -> Point<N> {
 
     if at_infinity(&point_a.x, &point_a.y) {
         return point_a.clone();
     }
 
+    // points with x = 0 have order 2
+    if gf2m::is_zero_words(&point_a.x) {
+        return infinity();
+    }
+
     /* Ref: https://hyperelliptic.org/EFD/g12o/auto-shortw-affine.html
        lambda = X1+Y1/X1
        X3 = lambda2+lambda+a2
-       Y3 = lambda*(X1+X3)+X3+Y1 */
+       Y3 = lambda*(X1+X3)+X3+Y1 = X1^2+(lambda+1)*X3
+       as lambda*X1 = X1^2+Y1 */
+
+    return curve.field.scope(|s| {
+        let (x1, y1) = (s.element(&point_a.x), s.element(&point_a.y));
+        let lbd = x1 + y1 * x1.inv();
+        let x3 = s.element(&curve.param_a) + lbd.square() + lbd;
 
-    let lbd;
-    let value_cx;
-
-    let neg_ax = gf2m::neg(&point_a.x, modulus);
-    lbd = gf2m::add(
-        &point_a.x,
-        &gf2m::reduce(
-            &gf2m::mul(&point_a.y, &neg_ax),
-            modulus
-        )
-    );
-    let temp = gf2m::add(
-        curve_a,
-        &gf2m::reduce(
-            &gf2m::sqr(&lbd),
-            modulus
-        )
-    );
-    value_cx = gf2m::add(&temp, &lbd);;
-
-    let value_cy = gf2m::reduce(
-        &gf2m::mul(&gf2m::add(&point_a.x, &value_cx), &lbd),
-        modulus
-    );
-    let value_cy = gf2m::add(&value_cy, &value_cx);
-    let value_cy = gf2m::add(&value_cy, &point_a.y);
-
-    return Point {x: value_cx, y: value_cy};
+        let mut acc = s.accumulator();
+        acc.sqr(x1);
+        acc.mul(lbd + s.one(), x3);
+        let y3 = acc.reduce();
+
+        return Point {x: *x3.value(), y: *y3.value()};
+    });
 }
 
 // FIXME: negative mul impossible
-pub fn point_mul(point: &Point, factor: &Field,
-                 modulus: &Field,
-                 curve_a: &Field) -> Point {
+pub fn point_mul<const N: usize, F: FieldBackend<N>>(point: &Point<N>, factor: &[u32; N], curve: &Curve<N, F>) -> Point<N> {
+
+    if cfg!(feature = "constant-time") {
+        return point_mul_ct(point, factor, curve);
+    }
+
+    return point_mul_vartime(point, factor, curve);
+}
 
-    if gf2m::is_zero(factor) {
+/* Ladder branching on factor bits, for public factors only. */
+fn point_mul_vartime<const N: usize, F: FieldBackend<N>>(point: &Point<N>, factor: &[u32; N], curve: &Curve<N, F>) -> Point<N> {
+
+    if gf2m::is_zero_words(factor) {
         return infinity();
     }
 
-    let mut j = gf2m::bit_size(factor) as i32;
+    let mut j = gf2m::bit_size(factor) as i32 - 1;
 
     let mut point_r0 = infinity();
     let mut point_r1 = point.clone();
 
     while j >= 0 {
         if gf2m::has_bit(factor, j as usize) {
-             point_r0 = point_add(
-                &point_r0, &point_r1,
-                modulus, curve_a
-            );
-            point_r1 = point_dbl(&point_r1, modulus, curve_a);
+            point_r0 = point_add(&point_r0, &point_r1, curve);
+            point_r1 = point_dbl(&point_r1, curve);
         }
         else {
-
-            point_r1 = point_add(
-                &point_r0, &point_r1,
-                modulus, curve_a
-            );
-            point_r0 = point_dbl(&point_r0, modulus, curve_a);
+            point_r1 = point_add(&point_r0, &point_r1, curve);
+            point_r0 = point_dbl(&point_r0, curve);
         }
         j = j - 1;
     }
@@ -185,49 +250,279 @@ pub fn point_mul(point: &Point, factor: &Field,
     return point_r0;
 }
 
-pub fn point_expand(compressed: &Field, curve: &Curve)-> Point {
+/* y^2 + xy = x^3 + a x^2 + b with reduced coordinates.
+   Infinity has no affine form and is not on the curve. */
+pub fn is_on_curve<const N: usize, F: FieldBackend<N>>(point: &Point<N>, curve: &Curve<N, F>) -> bool {
+    let field = &curve.field;
+    if at_infinity(&point.x, &point.y) {
+        return false;
+    }
+    if field.reduce(&point.x) != point.x || field.reduce(&point.y) != point.y {
+        return false;
+    }
+
+    return field.scope(|s| {
+        let (x, y) = (s.element(&point.x), s.element(&point.y));
+        let x2 = x.square();
+        let lhs = y.square() + x * y;
+        let rhs = x2 * x + s.element(&curve.param_a) * x2 + s.element(&curve.param_b);
+        return lhs == rhs;
+    });
+}
+
+/* Full public key check: on the curve, not infinity and in the
+   subgroup generated by the base point, order * point = O.
+   Costs a scalar multiplication, the key is public so the
+   variable time ladder is used. */
+pub fn is_valid_public<const N: usize, F: FieldBackend<N>>(point: &Point<N>, curve: &Curve<N, F>) -> bool {
+    if !is_on_curve(point, curve) {
+        return false;
+    }
+    let check = point_mul_vartime(point, &curve.order, curve);
+    return at_infinity(&check.x, &check.y);
+}
 
-    let mut value = compressed.clone();
-    if gf2m::is_zero(compressed) {
-        let mulpb = gf2m::reduce(
-            &gf2m::mul(&curve.param_b, &curve.param_b),
-            &curve.modulus
-        );
-        return Point {x: value, y: mulpb}
+/* factor + order or factor + 2 order, whichever has bit
+   t = bit_size(order) set. Both sums are computed and the choice is
+   masked, multiples of a point in the subgroup stay the same. */
+fn pad_scalar<const N: usize>(factor: &[u32; N], order: &[u32; N]) -> [u32; N] {
+    let t = gf2m::bit_size(order);
+    let (once, carry) = ct::int_add(factor, order);
+    let (twice, _) = ct::int_add(&once, order);
+
+    let top;
+    if t < N * 32 {
+        top = (once[t / 32] >> (t % 32)) & 1;
+    }
+    else {
+        top = carry;
     }
-    let k = value[0] & 1;
 
-    value[0] = value[0] & 0xFF_FF_FF_FE;
+    return ct::select(&twice, &once, top);
+}
 
-    let trace = gf2m::trace(&value, &curve.modulus);
-    if (trace == 1 && !gf2m::zero_one(&curve.param_a)) ||
-       (trace == 0 && gf2m::zero_one(&curve.param_a)) {
-        value[0] = value[0] | 1;
+/* Ref: Lopez, Dahab. Fast multiplication on elliptic curves over
+   GF(2^m) without precomputation, CHES 1999.
+
+   x-only Montgomery ladder on (X1 : Z1) = jP, (X2 : Z2) = (j + 1)P,
+   Madd: Z2 = (X1 Z2 + X2 Z1)^2,  X2 = x Z2 + X1 Z2 X2 Z1,
+   Mdouble: Z1 = X1^2 Z1^2,  X1 = X1^4 + b Z1^4.
+   The padded factor always has bit t = bit_size(order) set, so the
+   ladder starts from (P, 2P) and makes t steps for every factor.
+   y is recovered with one inversion (inv_ct) and the cases of kP or
+   (k + 1)P at infinity are selected by mask, so the sequence of
+   field operations depends only on the curve.
+   Factor is reduced by the order first (ct::int_reduce), the point
+   has to be in the subgroup, as points accepted by is_valid_public
+   are. */
+pub fn point_mul_ct<const N: usize, F: FieldBackend<N>>(point: &Point<N>, factor: &[u32; N], curve: &Curve<N, F>) -> Point<N> {
+    let scalar = pad_scalar(&ct::int_reduce(factor, &curve.order), &curve.order);
+    let steps = gf2m::bit_size(&curve.order);
+
+    return curve.field.scope(|s| {
+        let x = s.element(&point.x);
+        let y = s.element(&point.y);
+        let b = s.element(&curve.param_b);
+
+        // P and 2P = (x^4 + b : x^2)
+        let mut x1 = x;
+        let mut z1 = s.one();
+        let mut x2 = x.square().square() + b;
+        let mut z2 = x.square();
+
+        let mut swap = 0;
+        let mut j = steps;
+        while j > 0 {
+            j -= 1;
+            let bit = (scalar[j / 32] >> (j % 32)) & 1;
+            swap ^= bit;
+            x1.cswap(&mut x2, swap);
+            z1.cswap(&mut z2, swap);
+            swap = bit;
+
+            let t1 = x1 * z2;
+            let t2 = x2 * z1;
+            z2 = (t1 + t2).square();
+            x2 = x * z2 + t1 * t2;
+
+            let xx = x1.square();
+            let zz = z1.square();
+            x1 = xx.square() + b * zz.square();
+            z1 = xx * zz;
+        }
+        x1.cswap(&mut x2, swap);
+        z1.cswap(&mut z2, swap);
+
+        // Mxy: y = (x + x1)((X1 + x Z1)(X2 + x Z2) + (x^2 + y) Z1 Z2) / (x Z1 Z2) + y
+        let z1z2 = z1 * z2;
+        let inv = (x * z1z2).inv_ct();
+        let value_x = x1 * x * z2 * inv;
+        let temp = (x1 + x * z1) * (x2 + x * z2) + (x.square() + y) * z1z2;
+        let value_y = (x + value_x) * temp * inv + y;
+
+        // (k + 1)P = O, so kP = -P
+        let neg = ct::is_zero(z2.value()) as u32;
+        let value_x = value_x.select(x, neg);
+        let value_y = value_y.select(x + y, neg);
+
+        let inf = ct::is_zero(z1.value()) as u32 | at_infinity(&point.x, &point.y) as u32;
+        let value_x = value_x.select(s.zero(), inf);
+        let value_y = value_y.select(s.zero(), inf);
+
+        return Point {x: *value_x.value(), y: *value_y.value()};
+    });
+}
+
+/* Ref: Hankerson, Menezes, Vanstone. Guide to Elliptic Curve
+   Cryptography, algorithm 3.24.
+   Z3 = X1^2 Z1^2,  X3 = X1^4 + b Z1^4,
+   Y3 = b Z1^4 Z3 + X3 (a Z3 + Y1^2 + b Z1^4) */
+fn ld_dbl<'id, const N: usize, F: FieldBackend<N>>(point: &LdPoint<'id, N, F>, curve: &Curve<N, F>,
+                                                   pre: &Precomputed<N, F>,
+                                                   scope: &Scope<'id, N, F>) -> LdPoint<'id, N, F> {
+    if point.z.is_zero() || point.x.is_zero() {
+        return LdPoint::infinity(scope);
     }
-    let x2 = gf2m::reduce(&gf2m::mul(&value, &value), &curve.modulus);
-    let mut y = gf2m::reduce(&gf2m::mul(&x2, &value), &curve.modulus);
 
-    if gf2m::zero_one(&curve.param_a) {
-        y = gf2m::add(&y, &x2);
+    let z2 = point.z.square();
+    let x2 = point.x.square();
+    let value_z = z2 * x2;
+    let bz4 = z2.square().mul_prepared(&pre.prepared_b);
+    let value_x = x2.square() + bz4;
+
+    let mut temp = point.y.square() + bz4;
+    if curve.a_is_one() {
+        temp = temp + value_z;
     }
+    let mut acc = scope.accumulator();
+    acc.mul(value_x, temp);
+    acc.mul(bz4, value_z);
+    let value_y = acc.reduce();
 
-    y = gf2m::add(&y, &curve.param_b);
-    let invx2 = gf2m::neg(&x2, &curve.modulus);
-    y = gf2m::reduce(&gf2m::mul(&y, &invx2), &curve.modulus);
+    return LdPoint {x: value_x, y: value_y, z: value_z};
+}
 
-    y = gf2m::reduce(
-        &gf2m::squad_odd(&y, &curve.modulus, curve.field_m),
-        &curve.modulus
-    );
+/* Adds the base point, algorithm 3.25 of the same book.
+   A = y2 Z1^2 + Y1,  B = x2 Z1 + X1,  C = Z1 B,
+   Z3 = C^2,  X3 = A^2 + B^2 (C + a Z1^2) + A C,
+   Y3 = (A C + Z3)(X3 + x2 Z3) + (x2 + y2) Z3^2 */
+fn ld_add_base<'id, const N: usize, F: FieldBackend<N>>(point: &LdPoint<'id, N, F>, curve: &Curve<N, F>,
+                                                        pre: &Precomputed<N, F>,
+                                                        scope: &Scope<'id, N, F>) -> LdPoint<'id, N, F> {
+    let base = &pre.prepared_base;
+    if point.z.is_zero() {
+        return LdPoint::affine(&curve.base, scope);
+    }
 
-    let trace_y = gf2m::trace(&y, &curve.modulus);
+    let z2 = point.z.square();
+    let value_a = z2.mul_prepared(&base.y) + point.y;
+    let value_b = point.z.mul_prepared(&base.x) + point.x;
+    if value_b.is_zero() {
+        if value_a.is_zero() {
+            return ld_dbl(&LdPoint::affine(&curve.base, scope), curve, pre, scope);
+        }
+        return LdPoint::infinity(scope);
+    }
+
+    let value_c = point.z * value_b;
+    let value_z = value_c.square();
+    let mut acc = scope.accumulator();
+    acc.mul(value_a, value_c);
+    let value_e = acc.reduce();
 
-    if (k == 1 && trace_y == 0) ||
-       (k == 0 && trace_y == 1) {
-        y[0] = y[0] ^ 1;
+    let mut temp = value_c;
+    if curve.a_is_one() {
+        temp = temp + z2;
     }
+    // A C is already in acc
+    acc.mul(value_b.square(), temp);
+    acc.sqr(value_a);
+    let value_x = acc.reduce();
+
+    let value_f = value_x + value_z.mul_prepared(&base.x);
+    let mut acc = scope.accumulator();
+    acc.mul(value_e + value_z, value_f);
+    acc.mul_prepared(&base.sum, value_z.square());
+    let value_y = acc.reduce();
+
+    return LdPoint {x: value_x, y: value_y, z: value_z};
+}
+
+fn ld_to_affine<'id, const N: usize, F: FieldBackend<N>>(point: &LdPoint<'id, N, F>) -> Point<N> {
+    if point.z.is_zero() {
+        return infinity();
+    }
+    let inv_z = point.z.inv();
+    return Point {
+        x: *(point.x * inv_z).value(),
+        y: *(point.y * inv_z.square()).value(),
+    };
+}
 
-    y = gf2m::reduce(&gf2m::mul(&y, &value), &curve.modulus);
+/* factor * base. Double and add in projective coordinates with
+   precomputed base point, one inversion at the end. Branches on
+   factor bits, constant-time builds use point_mul_ct instead. */
+pub fn point_mul_base<const N: usize, F: FieldBackend<N>>(factor: &[u32; N], curve: &Curve<N, F>) -> Point<N> {
+
+    if cfg!(feature = "constant-time") {
+        return point_mul_ct(&curve.base, factor, curve);
+    }
+
+    return curve.with_precomputed(|pre| curve.field.scope(|s| {
+        let mut point = LdPoint::infinity(&s);
+        let mut j = gf2m::bit_size(factor);
+        while j > 0 {
+            j -= 1;
+            point = ld_dbl(&point, curve, pre, &s);
+            if gf2m::has_bit(factor, j) {
+                point = ld_add_base(&point, curve, pre, &s);
+            }
+        }
+
+        return ld_to_affine(&point);
+    }));
+}
+
+/* None when compressed is not x coordinate of a curve point. */
+pub fn point_expand<const N: usize, F: FieldBackend<N>>(compressed: &[u32; N], curve: &Curve<N, F>)-> Option<Point<N>> {
+
+    return curve.field.scope(|s| {
+        let mut value = compressed.clone();
+        if gf2m::is_zero_words(compressed) {
+            // y^2 = b
+            let sqrtb = s.element(&curve.param_b).sqrt();
+            return Some(Point {x: value, y: *sqrtb.value()});
+        }
+        let k = value[0] & 1;
+
+        value[0] = value[0] & 0xFF_FF_FF_FE;
+
+        let trace = s.element(&value).trace();
+        if (trace == 1 && !curve.a_is_one()) ||
+           (trace == 0 && curve.a_is_one()) {
+            value[0] = value[0] | 1;
+        }
+        let x = s.element(&value);
+        // y^2 + xy = x^3 + ax^2 + b, for y = xz: z^2 + z = x + a + b/x^2
+        let mut beta = curve.with_precomputed(|pre| x + x.square().inv().mul_prepared(&pre.prepared_b));
+
+        if curve.a_is_one() {
+            beta = beta + s.one();
+        }
+
+        let mut z = match beta.solve_quadratic() {
+            Some(root) => root,
+            None => return None,
+        };
+
+        let trace_z = z.trace();
+
+        // the other root is z + 1
+        if (k == 1 && trace_z == 0) ||
+           (k == 0 && trace_z == 1) {
+            z = z + s.one();
+        }
 
-    return Point {x: value, y: y};
+        return Some(Point {x: value, y: *(z * x).value()});
+    });
 }
@@ -1,13 +1,13 @@
 /*
 use num::{BigUint, Zero};
 use std::ops::{Add, Mul, Rem};
-use std::option::Option;
-use std::cmp::Ordering::{Greater}; */
+use std::option::Option; */
 
+use ct;
 use curve;
 use curve::{Point, Curve};
+use field::FieldBackend;
 use gf2m;
-use gf2m::Field;
 
 /*
 pub fn sign_helper(priv_d: &BigUint, tbs: &BigUint, rand_e: BigUint,
@@ -36,45 +36,32 @@ pub fn sign_helper(priv_d: &BigUint, tbs: &BigUint, rand_e: BigUint,
     return Some((s, r));
 } */
 
-/*
-fn gt(lft: &BigUint, rgt: &BigUint) -> bool {
-    return match lft.cmp(rgt) {
-        Greater => true,
-        _  => false,
-    };
-} */
-
-pub fn verify_helper(public: &Point,
-                     param_s: &Field, param_r: &Field,
-                     tbs: &Field,
-                     curve: &Curve) -> bool {
-    if gf2m::is_zero(param_s) {
+/* Checks 0 < r, s < n and the signature equation. The public key
+   is not validated here, that costs a scalar multiplication. Check
+   it once with curve::is_valid_public when the key is loaded. */
+pub fn verify_helper<const N: usize, F: FieldBackend<N>>(public: &Point<N>,
+                     param_s: &[u32; N], param_r: &[u32; N],
+                     tbs: &[u32; N],
+                     curve: &Curve<N, F>) -> bool {
+    if !ct::in_range(param_s, curve.order()) {
         return false;
     }
 
-    if gf2m::is_zero(param_r) {
+    if !ct::in_range(param_r, curve.order()) {
         return false;
     }
 
-    /*
-    if gt(param_s, &curve.order) {
-        return false;
-    } */
-
-    let point_mulq = curve::point_mul(public, &param_r, &curve.modulus, &curve.param_a);
-    let point_muls = curve::point_mul(&curve.base, &param_s, &curve.modulus, &curve.param_a);
+    let point_mulq = curve::point_mul(public, param_r, curve);
+    let point_muls = curve::point_mul_base(param_s, curve);
 
-    let point_r = curve::point_add(&point_mulq, &point_muls, &curve.modulus, &curve.param_a);
+    let point_r = curve::point_add(&point_mulq, &point_muls, curve);
 
     if curve::at_infinity(&point_r.x, &point_r.y) {
         return false;
     }
 
-    let compare_r = gf2m::reduce(
-        &gf2m::mul(tbs, &point_r.x),
-        &curve.modulus
-    );
-    let compare_r = gf2m::truncate(&compare_r, gf2m::bit_size(&curve.order));
+    let compare_r = curve.field().scope(|s| *(s.element(tbs) * s.element(&point_r.x)).value());
+    let compare_r = gf2m::truncate_words(&compare_r, gf2m::bit_size(curve.order()));
 
     return compare_r.eq(param_r);
 }
@@ -1,37 +1,45 @@
 use gf2m;
 use curve::{Curve, Point};
+use field::BinaryField;
 
+/* Polynomial basis field polynomials from DSTU 4145-2002
+   as (m, k1, k2, k3), trinomials have k2 = k3 = 0. */
+pub static FIELD_POLYNOMIALS: [(usize, usize, usize, usize); 11] = [
+    (163, 7, 6, 3),
+    (167, 6, 0, 0),
+    (173, 10, 2, 1),
+    (179, 4, 2, 1),
+    (191, 9, 0, 0),
+    (233, 9, 4, 1),
+    (239, 15, 2, 1),
+    (257, 12, 0, 0),
+    (307, 8, 4, 2),
+    (367, 21, 0, 0),
+    (431, 5, 3, 1),
+];
 
-pub fn curve_257() -> Curve {
-    return Curve {
-        param_a: gf2m::zero(),
-        param_b: gf2m::parse_hex(b"01CEF494720115657E18F938D7A7942394FF9425C1458C57861F9EEA6ADBE3BE10"),
-        order: gf2m::parse_hex(b"800000000000000000000000000000006759213af182e987d3e17714907d470d"),
-        base: Point {
-            x: gf2m::parse_hex(b"002A29EF207D0E9B6C55CD260B306C7E007AC491CA1B10C62334A9E8DCD8D20FB7"),
-            y: gf2m::parse_hex(b"010686D41FF744D4449FCCF6D8EEA03102E6812C93A9D60B978B702CF156D814EF"),
+pub fn curve_257() -> Curve<{ gf2m::field_words(257) }> {
+    return Curve::new(
+        [0; gf2m::field_words(257)],
+        gf2m::parse_hex_words(b"01CEF494720115657E18F938D7A7942394FF9425C1458C57861F9EEA6ADBE3BE10"),
+        gf2m::parse_hex_words(b"800000000000000000000000000000006759213af182e987d3e17714907d470d"),
+        Point {
+            x: gf2m::parse_hex_words(b"002A29EF207D0E9B6C55CD260B306C7E007AC491CA1B10C62334A9E8DCD8D20FB7"),
+            y: gf2m::parse_hex_words(b"010686D41FF744D4449FCCF6D8EEA03102E6812C93A9D60B978B702CF156D814EF"),
         },
-        field_m: 257,
-        field_k1: 12,
-        field_k2: 0,
-        field_k3: 0,
-        modulus: gf2m::compute_modulus(257, 12, 0, 0),
-    };
+        BinaryField::new(257, 12, 0, 0).unwrap(),
+    );
 }
 
-pub fn curve_431() -> Curve {
-    return Curve {
-        param_a: gf2m::one(),
-        param_b: gf2m::parse_hex(b"03CE10490F6A708FC26DFE8C3D27C4F94E690134D5BFF988D8D28AAEAEDE975936C66BAC536B18AE2DC312CA493117DAA469C640CAF3"),
-        order: gf2m::parse_hex(b"3FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFBA3175458009A8C0A724F02F81AA8A1FCBAF80D90C7A95110504CF"),
-        base: Point {
-            x: gf2m::parse_hex(b"1A62BA79D98133A16BBAE7ED9A8E03C32E0824D57AEF72F88986874E5AAE49C27BED49A2A95058068426C2171E99FD3B43C5947C857D"),
-            y: gf2m::parse_hex(b"70B5E1E14031C1F70BBEFE96BDDE66F451754B4CA5F48DA241F331AA396B8D1839A855C1769B1EA14BA53308B5E2723724E090E02DB9"),
+pub fn curve_431() -> Curve<{ gf2m::field_words(431) }> {
+    return Curve::new(
+        gf2m::one_words(),
+        gf2m::parse_hex_words(b"03CE10490F6A708FC26DFE8C3D27C4F94E690134D5BFF988D8D28AAEAEDE975936C66BAC536B18AE2DC312CA493117DAA469C640CAF3"),
+        gf2m::parse_hex_words(b"3FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFBA3175458009A8C0A724F02F81AA8A1FCBAF80D90C7A95110504CF"),
+        Point {
+            x: gf2m::parse_hex_words(b"1A62BA79D98133A16BBAE7ED9A8E03C32E0824D57AEF72F88986874E5AAE49C27BED49A2A95058068426C2171E99FD3B43C5947C857D"),
+            y: gf2m::parse_hex_words(b"70B5E1E14031C1F70BBEFE96BDDE66F451754B4CA5F48DA241F331AA396B8D1839A855C1769B1EA14BA53308B5E2723724E090E02DB9"),
         },
-        field_m: 431,
-        field_k1: 5,
-        field_k2: 3,
-        field_k3: 1,
-        modulus: gf2m::compute_modulus(431, 5, 3, 1),
-    };
+        BinaryField::new(431, 5, 3, 1).unwrap(),
+    );
 }
@@ -0,0 +1,687 @@
+use std::error;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::sync::OnceLock;
+
+use clmul;
+use ct;
+use gf2m;
+use gf2m::{FIELD_SIZE, Wide};
+use poly;
+
+/* Multiplicative inverse, gf2m::neg for raw arrays. */
+pub trait Inv {
+    type Output;
+    fn inv(self) -> Self::Output;
+}
+
+pub trait Square {
+    type Output;
+    fn square(self) -> Self::Output;
+}
+
+/* Field operations the curve layer runs on, over elements of N
+   32-bit words in whatever basis the implementation uses. BinaryField
+   is the default polynomial basis backend. Anything with the same
+   results (another basis, instrumented or constant-time variants)
+   can be plugged into curve::Curve. */
+pub trait FieldBackend<const N: usize = FIELD_SIZE> {
+    /* Operand prepared for repeated multiplication. */
+    type Prepared;
+
+    fn degree(&self) -> usize;
+
+    /* Multiplicative identity, not always gf2m::one(). */
+    fn unity(&self) -> [u32; N];
+
+    /* Reduces up to 2 * N words, as left by mul_acc. */
+    fn reduce(&self, value: &[u32]) -> [u32; N];
+
+    fn add(&self, value_a: &[u32; N], value_b: &[u32; N]) -> [u32; N] {
+        return gf2m::add_words(value_a, value_b);
+    }
+
+    fn mul(&self, value_a: &[u32; N], value_b: &[u32; N]) -> [u32; N];
+    fn sqr(&self, value: &[u32; N]) -> [u32; N];
+    fn sqrt(&self, value: &[u32; N]) -> [u32; N];
+    fn inv(&self, value: &[u32; N]) -> [u32; N];
+    fn trace(&self, value: &[u32; N]) -> u32;
+    fn solve_quadratic(&self, beta: &[u32; N]) -> Option<[u32; N]>;
+
+    /* Accumulates value_a * value_b into acc, reduce gives the sum.
+       Default adds reduced products. */
+    fn mul_acc(&self, acc: &mut Wide<N>, value_a: &[u32; N], value_b: &[u32; N]) {
+        acc[0] = gf2m::add_words(&acc[0], &self.mul(value_a, value_b));
+    }
+
+    fn sqr_acc(&self, acc: &mut Wide<N>, value: &[u32; N]) {
+        acc[0] = gf2m::add_words(&acc[0], &self.sqr(value));
+    }
+
+    fn prepare(&self, value: &[u32; N]) -> Self::Prepared;
+    fn mul_prepared(&self, prepared: &Self::Prepared, value: &[u32; N]) -> [u32; N];
+
+    /* mul_prepared counterpart of mul_acc. */
+    fn mul_prepared_acc(&self, acc: &mut Wide<N>, prepared: &Self::Prepared, value: &[u32; N]) {
+        acc[0] = gf2m::add_words(&acc[0], &self.mul_prepared(prepared, value));
+    }
+
+    /* Inverse by Itoh-Tsujii chain over mul and sqr, as gf2m::inv,
+       so it takes the same sequence of operations for every value.
+       Inverse of zero is zero, value has to be reduced. In fields
+       with m below 2 every element is its own inverse. */
+    fn inv_ct(&self, value: &[u32; N]) -> [u32; N] {
+        if self.degree() < 2 {
+            return *value;
+        }
+
+        let chain = self.degree() - 1;
+        let mut bit = (usize::BITS - chain.leading_zeros()) as usize - 1;
+        let mut beta = *value;
+        let mut k = 1;
+
+        while bit > 0 {
+            bit -= 1;
+            let mut temp = beta;
+            for _ in 0..k {
+                temp = self.sqr(&temp);
+            }
+            beta = self.mul(&temp, &beta);
+            k *= 2;
+
+            if (chain >> bit) & 1 == 1 {
+                beta = self.mul(&self.sqr(&beta), value);
+                k += 1;
+            }
+        }
+
+        return self.sqr(&beta);
+    }
+
+    /* Runs body with a Scope that creates elements of this field,
+       see FieldElement. */
+    fn scope<R>(&self, body: impl for<'id> FnOnce(Scope<'id, N, Self>) -> R) -> R where Self: Sized {
+        return body(Scope {field: self, brand: PhantomData});
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Reduction {
+    // word-level folding, see gf2m::reduce_sparse
+    Sparse,
+    // modulus terms are too close to the top, bit by bit
+    Generic,
+}
+
+/* GF(2^m) with polynomial basis x^m + x^k1 + x^k2 + x^k3 + 1,
+   elements are N words. Owns the modulus and everything derived
+   from it, field elements are created and operated on through it.
+   N has to hold m + 1 bits, gf2m::field_words(m) is the tightest. */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BinaryField<const N: usize = FIELD_SIZE> {
+    field_m: usize,
+    field_k1: usize,
+    field_k2: usize,
+    field_k3: usize,
+    modulus: [u32; N],
+    reduction: Reduction,
+    trace_mask: [u32; N],
+    half_trace: HalfTraceTable<N>,
+    // sqrt(x), see gf2m::sqrt_split
+    sqrt_x: [u32; N],
+}
+
+/* Half-trace of x^i, built on first use as it takes about m^2 / 2
+   squarings. Derived from the modulus, so it is left out of
+   comparisons. */
+#[derive(Clone, Debug, Default)]
+struct HalfTraceTable<const N: usize>(OnceLock<Vec<[u32; N]>>);
+
+impl<const N: usize> PartialEq for HalfTraceTable<N> {
+    fn eq(&self, _other: &HalfTraceTable<N>) -> bool {
+        return true;
+    }
+}
+
+impl<const N: usize> Eq for HalfTraceTable<N> {}
+
+/* Rejected field polynomial. */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FieldError {
+    // m does not fit into the element words or a term is not below m
+    Degree,
+    // polynomial has a non-trivial factor
+    Reducible,
+    // no type II optimal normal basis for this m
+    NoOptimalBasis,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match *self {
+            FieldError::Degree => write!(f, "field polynomial terms out of range"),
+            FieldError::Reducible => write!(f, "field polynomial is reducible"),
+            FieldError::NoOptimalBasis => write!(f, "no type II optimal normal basis"),
+        };
+    }
+}
+
+impl error::Error for FieldError {}
+
+impl<const N: usize> BinaryField<N> {
+    /* Pass zero for unused k, as in gf2m::compute_modulus.
+       Polynomial is checked for irreducibility with Rabin's test. */
+    pub fn new(field_m: usize, k1: usize, k2: usize, k3: usize) -> Result<BinaryField<N>, FieldError> {
+        if N > gf2m::MAX_WORDS || field_m < 2 || field_m >= N * 32 || k1 >= field_m || k2 >= field_m || k3 >= field_m {
+            return Err(FieldError::Degree);
+        }
+        if !poly::is_irreducible_rabin(&poly::from_terms(field_m, k1, k2, k3)) {
+            return Err(FieldError::Reducible);
+        }
+
+        let modulus = gf2m::compute_modulus_words(field_m, k1, k2, k3);
+        let reduction = match gf2m::sparse_terms(&modulus) {
+            Some(_) => Reduction::Sparse,
+            None => Reduction::Generic,
+        };
+
+        let mut field = BinaryField {
+            field_m: field_m,
+            field_k1: k1,
+            field_k2: k2,
+            field_k3: k3,
+            modulus: modulus,
+            reduction: reduction,
+            trace_mask: gf2m::trace_mask_words(&modulus),
+            half_trace: HalfTraceTable::default(),
+            sqrt_x: [0; N],
+        };
+        // sqrt(x) = x^(2^(m-1))
+        let mut sqrt_x = gf2m::shl_words(&gf2m::one_words(), 1);
+        for _ in 1..field_m {
+            sqrt_x = field.sqr(&sqrt_x);
+        }
+        field.sqrt_x = sqrt_x;
+        return Ok(field);
+    }
+
+    /* H(x^2i) = H(x^i)^2, only odd powers are computed
+       with the full chain of squarings. */
+    fn half_trace_table(&self) -> Vec<[u32; N]> {
+        let mut table: Vec<[u32; N]> = Vec::with_capacity(self.field_m);
+        let mut power = gf2m::one_words();
+        for i in 0..self.field_m {
+            let value = if i > 0 && i % 2 == 0 {
+                self.sqr(&table[i / 2])
+            } else {
+                let mut acc = power;
+                for _ in 0..(self.field_m / 2) {
+                    acc = self.add(&self.sqr(&self.sqr(&acc)), &power);
+                }
+                acc
+            };
+            table.push(value);
+            power = self.reduce(&gf2m::shl_words(&power, 1));
+        }
+        return table;
+    }
+
+    pub fn degree(&self) -> usize {
+        return self.field_m;
+    }
+
+    /* (m, k1, k2, k3) this field was built from. */
+    pub fn terms(&self) -> (usize, usize, usize, usize) {
+        return (self.field_m, self.field_k1, self.field_k2, self.field_k3);
+    }
+
+    pub fn modulus(&self) -> &[u32; N] {
+        return &self.modulus;
+    }
+
+    pub fn trace_mask(&self) -> &[u32; N] {
+        return &self.trace_mask;
+    }
+
+    /* Takes up to 2 * N words. */
+    pub fn reduce(&self, value: &[u32]) -> [u32; N] {
+        return match self.reduction {
+            Reduction::Sparse => gf2m::reduce_sparse_words(
+                value,
+                self.field_m, self.field_k1, self.field_k2, self.field_k3
+            ),
+            Reduction::Generic if cfg!(feature = "constant-time") => ct::reduce(value, &self.modulus),
+            Reduction::Generic => gf2m::reduce_bitwise_words(value, &self.modulus),
+        };
+    }
+
+    pub fn add(&self, value_a: &[u32; N], value_b: &[u32; N]) -> [u32; N] {
+        return gf2m::add_words(value_a, value_b);
+    }
+
+    pub fn mul(&self, value_a: &[u32; N], value_b: &[u32; N]) -> [u32; N] {
+        if self.reduction == Reduction::Sparse && !clmul::is_available() {
+            return gf2m::mul_sparse_words(
+                value_a, value_b,
+                self.field_m, self.field_k1, self.field_k2, self.field_k3
+            );
+        }
+        return self.reduce(gf2m::mul_wide(value_a, value_b).as_flattened());
+    }
+
+    /* Unreduced product added to acc, see gf2m::mul_acc. */
+    pub fn mul_acc(&self, acc: &mut Wide<N>, value_a: &[u32; N], value_b: &[u32; N]) {
+        add_wide(acc, &gf2m::mul_wide(value_a, value_b));
+    }
+
+    pub fn sqr_acc(&self, acc: &mut Wide<N>, value: &[u32; N]) {
+        add_wide(acc, &gf2m::sqr_wide(value));
+    }
+
+    pub fn sqr(&self, value: &[u32; N]) -> [u32; N] {
+        if self.reduction == Reduction::Sparse && !clmul::is_available() {
+            return gf2m::sqr_sparse_words(value, self.field_m, self.field_k1, self.field_k2, self.field_k3);
+        }
+        return self.reduce(gf2m::sqr_wide(value).as_flattened());
+    }
+
+    /* Value has to be reduced, see gf2m::sqrt_split. */
+    pub fn sqrt(&self, value: &[u32; N]) -> [u32; N] {
+        let (even, odd) = gf2m::split_words(value);
+        return self.add(&even, &self.mul(&odd, &self.sqrt_x));
+    }
+
+    pub fn inv(&self, value: &[u32; N]) -> [u32; N] {
+        if cfg!(feature = "constant-time") {
+            return ct::inv(value, &self.modulus);
+        }
+        return gf2m::neg_words(value, &self.modulus);
+    }
+
+    /* Encoded element length, ceil(m / 8) bytes. */
+    pub fn byte_len(&self) -> usize {
+        return self.field_m.div_ceil(8);
+    }
+
+    pub fn to_bytes_le(&self, value: &[u32; N]) -> Vec<u8> {
+        let mut ret = Vec::with_capacity(N * 4);
+        for word in value.iter() {
+            ret.extend_from_slice(&word.to_le_bytes());
+        }
+        ret.truncate(self.byte_len());
+        return ret;
+    }
+
+    pub fn to_bytes_be(&self, value: &[u32; N]) -> Vec<u8> {
+        let mut ret = self.to_bytes_le(value);
+        ret.reverse();
+        return ret;
+    }
+
+    pub fn from_bytes_le(&self, data: &[u8]) -> Result<[u32; N], gf2m::ParseError> {
+        return gf2m::try_from_bytes_le_words(data, self.field_m);
+    }
+
+    pub fn from_bytes_be(&self, data: &[u8]) -> Result<[u32; N], gf2m::ParseError> {
+        return gf2m::try_from_bytes_be_words(data, self.field_m);
+    }
+
+    pub fn from_hex(&self, data: &[u8]) -> Result<[u32; N], gf2m::ParseError> {
+        return gf2m::try_parse_hex_words(data, self.field_m);
+    }
+
+    pub fn to_hex(&self, value: &[u32; N]) -> String {
+        return gf2m::to_hex(value);
+    }
+
+    /* Constant time, value has to be reduced. */
+    pub fn trace(&self, value: &[u32; N]) -> u32 {
+        return gf2m::trace_masked_words(value, &self.trace_mask);
+    }
+
+    /* Half-trace from the table, constant time once the table is
+       built. None for even m, where it is not defined. Value has
+       to be reduced. */
+    pub fn half_trace(&self, value: &[u32; N]) -> Option<[u32; N]> {
+        if self.field_m % 2 == 0 {
+            return None;
+        }
+        let table = self.half_trace.0.get_or_init(|| self.half_trace_table());
+        let mut ret = [0; N];
+        for i in 0..self.field_m {
+            let choice = 0u32.wrapping_sub((value[i / 32] >> (i % 32)) & 1);
+            let row = &table[i];
+            for j in 0..N {
+                ret[j] ^= row[j] & choice;
+            }
+        }
+        return Some(ret);
+    }
+}
+
+#[inline]
+fn add_wide<const N: usize>(acc: &mut Wide<N>, value: &Wide<N>) {
+    acc[0] = gf2m::add_words(&acc[0], &value[0]);
+    acc[1] = gf2m::add_words(&acc[1], &value[1]);
+}
+
+impl<const N: usize> FieldBackend<N> for BinaryField<N> {
+    type Prepared = gf2m::PreparedMultiplicand<N>;
+
+    fn degree(&self) -> usize {
+        return self.field_m;
+    }
+
+    fn unity(&self) -> [u32; N] {
+        return gf2m::one_words();
+    }
+
+    fn reduce(&self, value: &[u32]) -> [u32; N] {
+        return BinaryField::reduce(self, value);
+    }
+
+    fn mul(&self, value_a: &[u32; N], value_b: &[u32; N]) -> [u32; N] {
+        return BinaryField::mul(self, value_a, value_b);
+    }
+
+    fn sqr(&self, value: &[u32; N]) -> [u32; N] {
+        return BinaryField::sqr(self, value);
+    }
+
+    fn sqrt(&self, value: &[u32; N]) -> [u32; N] {
+        return BinaryField::sqrt(self, value);
+    }
+
+    fn inv(&self, value: &[u32; N]) -> [u32; N] {
+        return BinaryField::inv(self, value);
+    }
+
+    fn trace(&self, value: &[u32; N]) -> u32 {
+        return BinaryField::trace(self, value);
+    }
+
+    fn solve_quadratic(&self, beta: &[u32; N]) -> Option<[u32; N]> {
+        return solve_quadratic(beta, self);
+    }
+
+    /* Unreduced, reduction is linear. */
+    fn mul_acc(&self, acc: &mut Wide<N>, value_a: &[u32; N], value_b: &[u32; N]) {
+        BinaryField::mul_acc(self, acc, value_a, value_b);
+    }
+
+    fn sqr_acc(&self, acc: &mut Wide<N>, value: &[u32; N]) {
+        BinaryField::sqr_acc(self, acc, value);
+    }
+
+    fn prepare(&self, value: &[u32; N]) -> gf2m::PreparedMultiplicand<N> {
+        return gf2m::PreparedMultiplicand::new(value);
+    }
+
+    fn mul_prepared(&self, prepared: &gf2m::PreparedMultiplicand<N>, value: &[u32; N]) -> [u32; N] {
+        return BinaryField::reduce(self, prepared.mul(value).as_flattened());
+    }
+
+    fn mul_prepared_acc(&self, acc: &mut Wide<N>, prepared: &gf2m::PreparedMultiplicand<N>, value: &[u32; N]) {
+        add_wide(acc, &prepared.mul(value));
+    }
+}
+
+/* Root of z^2 + z = beta, the other one is z + 1. None when there
+   is none, that is when Tr(beta) = 1, and always for even m as
+   the half-trace is only defined for odd m. Constant time in beta
+   apart from the returned variant. */
+pub fn solve_quadratic<const N: usize>(beta: &[u32; N], field: &BinaryField<N>) -> Option<[u32; N]> {
+    let beta = field.reduce(beta);
+    let root = match field.half_trace(&beta) {
+        Some(root) => root,
+        None => return None,
+    };
+    if field.trace(&beta) == 1 {
+        return None;
+    }
+    return Some(root);
+}
+
+/* Lifetime that only unifies with itself, one per call of
+   FieldBackend::scope. */
+type Brand<'id> = PhantomData<fn(&'id ()) -> &'id ()>;
+
+/** Handle to a field inside FieldBackend::scope, the only way to
+   create FieldElement values. Elements carry the scope lifetime 'id
+   and every scope gets a fresh one, so elements of two fields (or of
+   two scopes of one field) do not type check together:
+
+   ```
+   use dstu4145::field::{BinaryField, FieldBackend, Inv};
+
+   let field: BinaryField = BinaryField::new(257, 12, 0, 0).unwrap();
+   let square = field.scope(|s| {
+       let x = s.element(&dstu4145::gf2m::parse_hex(b"2"));
+       assert_eq!(x * x.inv(), s.one());
+       return *(x * x).value();
+   });
+   assert_eq!(square, dstu4145::gf2m::parse_hex(b"4"));
+   ```
+
+   ```compile_fail
+   use dstu4145::field::{BinaryField, FieldBackend};
+
+   let field_257: BinaryField = BinaryField::new(257, 12, 0, 0).unwrap();
+   let field_431: BinaryField = BinaryField::new(431, 5, 3, 1).unwrap();
+   field_257.scope(|s| field_431.scope(|t| {
+       let _ = s.one() * t.one();
+   }));
+   ``` */
+pub struct Scope<'id, const N: usize, F: FieldBackend<N>> {
+    field: &'id F,
+    brand: Brand<'id>,
+}
+
+impl<'id, const N: usize, F: FieldBackend<N>> Clone for Scope<'id, N, F> {
+    fn clone(&self) -> Scope<'id, N, F> { *self }
+}
+
+impl<'id, const N: usize, F: FieldBackend<N>> Copy for Scope<'id, N, F> {}
+
+impl<'id, const N: usize, F: FieldBackend<N>> Scope<'id, N, F> {
+    pub fn field(&self) -> &'id F {
+        return self.field;
+    }
+
+    /* Reduces value. */
+    pub fn element(&self, value: &[u32; N]) -> FieldElement<'id, N, F> {
+        return self.wrap(self.field.reduce(value));
+    }
+
+    pub fn zero(&self) -> FieldElement<'id, N, F> {
+        return self.wrap([0; N]);
+    }
+
+    pub fn one(&self) -> FieldElement<'id, N, F> {
+        return self.wrap(self.field.unity());
+    }
+
+    /* Sum of products reduced once, see FieldBackend::mul_acc. */
+    pub fn accumulator(&self) -> Accumulator<'id, N, F> {
+        return Accumulator {scope: *self, acc: [[0; N]; 2]};
+    }
+
+    #[inline]
+    fn wrap(&self, value: [u32; N]) -> FieldElement<'id, N, F> {
+        return FieldElement {scope: *self, value: value};
+    }
+}
+
+/* Reduced element of the field of Scope 'id. Operators
+   run on that field's backend. */
+pub struct FieldElement<'id, const N: usize, F: FieldBackend<N>> {
+    scope: Scope<'id, N, F>,
+    value: [u32; N],
+}
+
+impl<'id, const N: usize, F: FieldBackend<N>> Clone for FieldElement<'id, N, F> {
+    fn clone(&self) -> FieldElement<'id, N, F> { *self }
+}
+
+impl<'id, const N: usize, F: FieldBackend<N>> Copy for FieldElement<'id, N, F> {}
+
+impl<'id, const N: usize, F: FieldBackend<N>> FieldElement<'id, N, F> {
+    pub fn value(&self) -> &[u32; N] {
+        return &self.value;
+    }
+
+    pub fn is_zero(&self) -> bool {
+        return gf2m::is_zero_words(&self.value);
+    }
+
+    pub fn sqrt(self) -> FieldElement<'id, N, F> {
+        return self.scope.wrap(self.scope.field.sqrt(&self.value));
+    }
+
+    pub fn trace(self) -> u32 {
+        return self.scope.field.trace(&self.value);
+    }
+
+    /* Root z of z^2 + z = self, see FieldBackend::solve_quadratic. */
+    pub fn solve_quadratic(self) -> Option<FieldElement<'id, N, F>> {
+        return self.scope.field.solve_quadratic(&self.value).map(|root| self.scope.wrap(root));
+    }
+
+    /* See FieldBackend::inv_ct. */
+    pub fn inv_ct(self) -> FieldElement<'id, N, F> {
+        return self.scope.wrap(self.scope.field.inv_ct(&self.value));
+    }
+
+    /* other when choice is 1, self when it is 0, without branches. */
+    pub fn select(self, other: FieldElement<'id, N, F>, choice: u32) -> FieldElement<'id, N, F> {
+        return self.scope.wrap(ct::select(&self.value, &other.value, choice));
+    }
+
+    /* Swaps with other when choice is 1, without branches. */
+    pub fn cswap(&mut self, other: &mut FieldElement<'id, N, F>, choice: u32) {
+        ct::cswap(&mut self.value, &mut other.value, choice);
+    }
+
+    /* prepared has to come from prepare of the same field. */
+    pub fn mul_prepared(self, prepared: &F::Prepared) -> FieldElement<'id, N, F> {
+        return self.scope.wrap(self.scope.field.mul_prepared(prepared, &self.value));
+    }
+
+    fn hex(&self, upper: bool) -> String {
+        let ret = gf2m::to_hex(&self.value);
+        if upper {
+            return ret.to_uppercase();
+        }
+        return ret;
+    }
+}
+
+/* Unreduced sum of products of elements of one scope. */
+pub struct Accumulator<'id, const N: usize, F: FieldBackend<N>> {
+    scope: Scope<'id, N, F>,
+    acc: Wide<N>,
+}
+
+impl<'id, const N: usize, F: FieldBackend<N>> Accumulator<'id, N, F> {
+    pub fn mul(&mut self, value_a: FieldElement<'id, N, F>, value_b: FieldElement<'id, N, F>) {
+        self.scope.field.mul_acc(&mut self.acc, &value_a.value, &value_b.value);
+    }
+
+    pub fn sqr(&mut self, value: FieldElement<'id, N, F>) {
+        self.scope.field.sqr_acc(&mut self.acc, &value.value);
+    }
+
+    pub fn mul_prepared(&mut self, prepared: &F::Prepared, value: FieldElement<'id, N, F>) {
+        self.scope.field.mul_prepared_acc(&mut self.acc, prepared, &value.value);
+    }
+
+    pub fn reduce(&self) -> FieldElement<'id, N, F> {
+        return self.scope.wrap(self.scope.field.reduce(self.acc.as_flattened()));
+    }
+}
+
+impl<'id, const N: usize, F: FieldBackend<N>> PartialEq for FieldElement<'id, N, F> {
+    fn eq(&self, other: &FieldElement<'id, N, F>) -> bool {
+        return self.value == other.value;
+    }
+}
+
+impl<'id, const N: usize, F: FieldBackend<N>> Eq for FieldElement<'id, N, F> {}
+
+impl<'id, const N: usize, F: FieldBackend<N>> Add for FieldElement<'id, N, F> {
+    type Output = FieldElement<'id, N, F>;
+
+    fn add(self, other: FieldElement<'id, N, F>) -> FieldElement<'id, N, F> {
+        return self.scope.wrap(self.scope.field.add(&self.value, &other.value));
+    }
+}
+
+/* Characteristic 2: subtraction is addition. */
+impl<'id, const N: usize, F: FieldBackend<N>> Sub for FieldElement<'id, N, F> {
+    type Output = FieldElement<'id, N, F>;
+
+    // subtraction is addition in characteristic 2
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, other: FieldElement<'id, N, F>) -> FieldElement<'id, N, F> {
+        return self + other;
+    }
+}
+
+impl<'id, const N: usize, F: FieldBackend<N>> Mul for FieldElement<'id, N, F> {
+    type Output = FieldElement<'id, N, F>;
+
+    fn mul(self, other: FieldElement<'id, N, F>) -> FieldElement<'id, N, F> {
+        return self.scope.wrap(self.scope.field.mul(&self.value, &other.value));
+    }
+}
+
+/* Additive inverse, every element is its own. */
+impl<'id, const N: usize, F: FieldBackend<N>> Neg for FieldElement<'id, N, F> {
+    type Output = FieldElement<'id, N, F>;
+
+    fn neg(self) -> FieldElement<'id, N, F> {
+        return self;
+    }
+}
+
+/* Inverse of zero is zero. */
+impl<'id, const N: usize, F: FieldBackend<N>> Inv for FieldElement<'id, N, F> {
+    type Output = FieldElement<'id, N, F>;
+
+    fn inv(self) -> FieldElement<'id, N, F> {
+        return self.scope.wrap(self.scope.field.inv(&self.value));
+    }
+}
+
+impl<'id, const N: usize, F: FieldBackend<N>> Square for FieldElement<'id, N, F> {
+    type Output = FieldElement<'id, N, F>;
+
+    fn square(self) -> FieldElement<'id, N, F> {
+        return self.scope.wrap(self.scope.field.sqr(&self.value));
+    }
+}
+
+impl<'id, const N: usize, F: FieldBackend<N>> fmt::LowerHex for FieldElement<'id, N, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return f.pad_integral(true, "0x", &self.hex(false));
+    }
+}
+
+impl<'id, const N: usize, F: FieldBackend<N>> fmt::UpperHex for FieldElement<'id, N, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return f.pad_integral(true, "0x", &self.hex(true));
+    }
+}
+
+impl<'id, const N: usize, F: FieldBackend<N>> fmt::Display for FieldElement<'id, N, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return fmt::LowerHex::fmt(self, f);
+    }
+}
+
+impl<'id, const N: usize, F: FieldBackend<N>> fmt::Debug for FieldElement<'id, N, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(f, "FieldElement({:#x})", self);
+    }
+}
@@ -0,0 +1,468 @@
+use gf2m;
+
+const FIELD_SIZE: usize = 8;
+const WORD_SIZE: usize = 64;
+
+pub type Field = [u64; FIELD_SIZE];
+pub type FieldMul = [u64; FIELD_SIZE * 2];
+
+pub fn from_field32(value: &[u32]) -> Field {
+    let mut ret: Field = [0; FIELD_SIZE];
+    for i in 0..FIELD_SIZE {
+        ret[i] = (value[i * 2] as u64) | ((value[i * 2 + 1] as u64) << 32);
+    }
+    return ret;
+}
+
+pub fn to_field32(value: &Field) -> gf2m::Field {
+    let mut ret: gf2m::Field = gf2m::zero();
+    for i in 0..FIELD_SIZE {
+        ret[i * 2] = value[i] as u32;
+        ret[i * 2 + 1] = (value[i] >> 32) as u32;
+    }
+    return ret;
+}
+
+pub fn from_field_mul32(value: &[u32]) -> FieldMul {
+    let mut ret: FieldMul = [0; FIELD_SIZE * 2];
+    for i in 0..value.len() {
+        ret[i >> 1] |= (value[i] as u64) << ((i % 2) * 32);
+    }
+    return ret;
+}
+
+pub fn to_field_mul32(value: &FieldMul) -> gf2m::FieldMul {
+    let mut ret: gf2m::FieldMul = [0; FIELD_SIZE * 4];
+    for i in 0..(FIELD_SIZE * 2) {
+        ret[i * 2] = value[i] as u32;
+        ret[i * 2 + 1] = (value[i] >> 32) as u32;
+    }
+    return ret;
+}
+
+#[inline]
+fn word_bits(value: u64) -> usize {
+    return WORD_SIZE - (value.leading_zeros() as usize);
+}
+
+#[inline]
+pub fn bit_size(value: &[u64]) -> usize {
+    let mut max_word = 0;
+
+    for size in 0..value.len() {
+        let mask = if value[size] == 0 {
+            0
+        } else {
+            0xFF_FF
+        };
+        if (mask & size) > max_word {
+            max_word = mask & size;
+        }
+    }
+
+    return (max_word * WORD_SIZE) + word_bits(value[max_word]);
+}
+
+pub fn shl(value: &Field, shift: usize) -> Field {
+    let word_shift = shift / WORD_SIZE;
+    let bit_shift = shift % WORD_SIZE;
+
+    let mut ret: Field = [0; FIELD_SIZE];
+    ret[word_shift] = value[0] << bit_shift;
+    for i in 1..(FIELD_SIZE - word_shift) {
+        ret[i + word_shift] = value[i] << bit_shift;
+        if bit_shift > 0 {
+            ret[i + word_shift] |= value[i - 1] >> (WORD_SIZE - bit_shift);
+        }
+    }
+    return ret;
+}
+
+pub fn shll(value: &FieldMul, shift: usize) -> FieldMul {
+    let word_shift = shift / WORD_SIZE;
+    let bit_shift = shift % WORD_SIZE;
+
+    let mut ret: FieldMul = [0; FIELD_SIZE * 2];
+    ret[word_shift] = value[0] << bit_shift;
+    for i in 1..((FIELD_SIZE * 2) - word_shift) {
+        ret[i + word_shift] = value[i] << bit_shift;
+        if bit_shift > 0 {
+            ret[i + word_shift] |= value[i - 1] >> (WORD_SIZE - bit_shift);
+        }
+    }
+    return ret;
+}
+
+pub fn add(value_a: &Field, value_b: &Field) -> Field {
+    let mut ret: Field = [0; FIELD_SIZE];
+    for i in 0..FIELD_SIZE {
+        ret[i] = value_a[i] ^ value_b[i];
+    }
+    return ret;
+}
+
+pub fn reduce(value: &[u64], modulus: &Field) -> Field {
+    let mut ret: FieldMul = [0; FIELD_SIZE * 2];
+    ret[..value.len()].copy_from_slice(value);
+
+    let mut bigmodulus: FieldMul = [0; FIELD_SIZE * 2];
+    bigmodulus[..FIELD_SIZE].copy_from_slice(modulus);
+
+    let mod_size = bit_size(modulus) as i32;
+    let mut size_difference = (bit_size(&ret) as i32) - mod_size;
+    while size_difference >= 0 {
+        let mask = shll(&bigmodulus, size_difference as usize);
+        for i in 0..(FIELD_SIZE * 2) {
+            ret[i] ^= mask[i];
+        }
+        size_difference = (bit_size(&ret) as i32) - mod_size;
+    }
+
+    let mut ret_field: Field = [0; FIELD_SIZE];
+    ret_field.copy_from_slice(&ret[..FIELD_SIZE]);
+    return ret_field;
+}
+
+/* Shift-and-add over every bit of b, selected by mask, as
+   gf2m::mul_1x1_u32_masked. Used in constant-time builds. */
+#[inline]
+pub fn mul_1x1_u64_masked(a: u64, b: u64) -> (u64, u64) {
+    let mut l = a & 0u64.wrapping_sub(b & 1);
+    let mut h = 0;
+    for i in 1..WORD_SIZE {
+        let mask = 0u64.wrapping_sub((b >> i) & 1);
+        l ^= (a << i) & mask;
+        h ^= (a >> (WORD_SIZE - i)) & mask;
+    }
+    return (l, h);
+}
+
+/* Same windowed method as gf2m::mul_1x1_u32, with 4-bit window
+   for 64-bit words. Top three bits of a do not fit into the
+   table and are compensated separately. */
+#[inline]
+pub fn mul_1x1_u64(a: u64, b: u64) -> (u64, u64) {
+    if cfg!(feature = "constant-time") {
+        return mul_1x1_u64_masked(a, b);
+    }
+
+    let top3b = a >> 61;
+
+    let a1 = a & 0x1FFF_FFFF_FFFF_FFFF;
+    let a2 = a1 << 1;
+    let a4 = a2 << 1;
+    let a8 = a4 << 1;
+
+    let tab = [
+        0, a1, a2, a1 ^ a2,
+        a4, a1 ^ a4, a2 ^ a4, a1 ^ a2 ^ a4,
+        a8, a1 ^ a8, a2 ^ a8, a1 ^ a2 ^ a8,
+        a4 ^ a8, a1 ^ a4 ^ a8, a2 ^ a4 ^ a8, a1 ^ a2 ^ a4 ^ a8,
+    ];
+
+    let mut l = tab[(b & 0xF) as usize];
+    let mut h = 0;
+    let mut shift = 4;
+    while shift < WORD_SIZE {
+        let s = tab[(b >> shift & 0xF) as usize];
+        l ^= s << shift;
+        h ^= s >> (WORD_SIZE - shift);
+        shift += 4;
+    }
+
+    let m1 = 0u64.wrapping_sub(top3b & 1);
+    let m2 = 0u64.wrapping_sub((top3b >> 1) & 1);
+    let m4 = 0u64.wrapping_sub(top3b >> 2);
+    l ^= (b << 61) & m1; h ^= (b >> 3) & m1;
+    l ^= (b << 62) & m2; h ^= (b >> 2) & m2;
+    l ^= (b << 63) & m4; h ^= (b >> 1) & m4;
+
+    return (l, h);
+}
+
+#[inline]
+pub fn mul_2x2_u64(a1: u64, a0: u64, b1: u64, b0: u64) -> (u64, u64, u64, u64) {
+    let (z0l, z0h) = mul_1x1_u64(a0, b0);
+    let (z1l, z1h) = mul_1x1_u64(a1, b1);
+    let (z2l, z2h) = mul_1x1_u64(a0 ^ a1, b0 ^ b1);
+
+    let z1l = z1l ^ z0h ^ z1h ^ z2h;
+    let z0h = z1h ^ z1l ^ z0l ^ z2l ^ z2h;
+    return (z0l, z0h, z1l, z1h);
+}
+
+/* Three-term Karatsuba, six word multiplications instead of nine:
+   c1 = (a0+a1)(b0+b1) + a0b0 + a1b1
+   c2 = (a0+a2)(b0+b2) + a0b0 + a1b1 + a2b2
+   c3 = (a1+a2)(b1+b2) + a1b1 + a2b2 */
+#[inline]
+pub fn mul_3x3_u64(a2: u64, a1: u64, a0: u64, b2: u64, b1: u64, b0: u64) -> [u64; 6] {
+    let (p0l, p0h) = mul_1x1_u64(a0, b0);
+    let (p1l, p1h) = mul_1x1_u64(a1, b1);
+    let (p2l, p2h) = mul_1x1_u64(a2, b2);
+    let (p01l, p01h) = mul_1x1_u64(a0 ^ a1, b0 ^ b1);
+    let (p02l, p02h) = mul_1x1_u64(a0 ^ a2, b0 ^ b2);
+    let (p12l, p12h) = mul_1x1_u64(a1 ^ a2, b1 ^ b2);
+
+    let c1l = p01l ^ p0l ^ p1l;
+    let c1h = p01h ^ p0h ^ p1h;
+    let c2l = p02l ^ p0l ^ p1l ^ p2l;
+    let c2h = p02h ^ p0h ^ p1h ^ p2h;
+    let c3l = p12l ^ p1l ^ p2l;
+    let c3h = p12h ^ p1h ^ p2h;
+
+    return [
+        p0l,
+        p0h ^ c1l,
+        c1h ^ c2l,
+        c2h ^ c3l,
+        c3h ^ p2l,
+        p2h,
+    ];
+}
+
+#[inline]
+fn fits_3_words(value: &Field) -> bool {
+    let mut acc = 0;
+    for i in 3..FIELD_SIZE {
+        acc |= value[i];
+    }
+    return acc == 0;
+}
+
+pub fn mul(value_a: &Field, value_b: &Field) -> FieldMul {
+    let mut result: FieldMul = [0; FIELD_SIZE * 2];
+
+    // 163..191 bit fields only ever use the lower three words.
+    if !cfg!(feature = "constant-time") && fits_3_words(value_a) && fits_3_words(value_b) {
+        let r = mul_3x3_u64(
+            value_a[2], value_a[1], value_a[0],
+            value_b[2], value_b[1], value_b[0]
+        );
+        result[..6].copy_from_slice(&r);
+        return result;
+    }
+
+    let mut j = 0;
+    while j < FIELD_SIZE {
+        let y0 = value_b[j];
+        let y1 = value_b[j + 1];
+
+        let mut i = 0;
+        while i < FIELD_SIZE {
+            let x0 = value_a[i];
+            let x1 = value_a[i + 1];
+
+            let (l0, h0, l1, h1) = mul_2x2_u64(x1, x0, y1, y0);
+            result[j + i] ^= l0;
+            result[j + i + 1] ^= h0;
+            result[j + i + 2] ^= l1;
+            result[j + i + 3] ^= h1;
+
+            i += 2;
+        }
+
+        j += 2;
+    }
+
+    return result;
+}
+
+pub fn mul_field32(value_a: &gf2m::Field, value_b: &gf2m::Field) -> gf2m::FieldMul {
+    return to_field_mul32(&mul(&from_field32(value_a), &from_field32(value_b)));
+}
+
+pub fn reduce_field32(value: &[u32], modulus: &gf2m::Field) -> gf2m::Field {
+    return to_field32(&reduce(&from_field_mul32(value), &from_field32(modulus)));
+}
+
+/* Widest operand in limbs, gf2m::MAX_WORDS 32-bit words. */
+const MAX_LIMBS: usize = gf2m::MAX_WORDS / 2;
+
+type Limbs = [u64; MAX_LIMBS];
+// room for a full product and a fold spilling one limb over
+type LimbsWide = [u64; MAX_LIMBS * 2 + 1];
+
+fn to_limbs(value: &[u32]) -> Limbs {
+    let mut ret = [0; MAX_LIMBS];
+    for i in 0..value.len() {
+        ret[i >> 1] |= (value[i] as u64) << ((i % 2) * 32);
+    }
+    return ret;
+}
+
+fn from_limbs(value: &[u64], out: &mut [u32]) {
+    for i in 0..out.len() {
+        out[i] = (value[i >> 1] >> ((i % 2) * 32)) as u32;
+    }
+}
+
+/* Limbs needed for words 32-bit words. */
+#[inline]
+fn limb_count(words: usize) -> usize {
+    return words.div_ceil(2);
+}
+
+/* out = a * b for limb slices of the same length, out has twice
+   that length and is overwritten. Halves are split as in
+   gf2m::karatsuba until the 1, 2 or 3 limb kernels are left. */
+fn karatsuba(a: &[u64], b: &[u64], out: &mut [u64]) {
+    let size = a.len();
+    if size == 1 {
+        let (l, h) = mul_1x1_u64(a[0], b[0]);
+        out[0] = l;
+        out[1] = h;
+        return;
+    }
+    if size == 2 {
+        let (l0, h0, l1, h1) = mul_2x2_u64(a[1], a[0], b[1], b[0]);
+        out[0] = l0;
+        out[1] = h0;
+        out[2] = l1;
+        out[3] = h1;
+        return;
+    }
+    if size == 3 {
+        out.copy_from_slice(&mul_3x3_u64(a[2], a[1], a[0], b[2], b[1], b[0]));
+        return;
+    }
+
+    let half = size.div_ceil(2);
+    let rest = size - half;
+
+    let mut sum_a = [0; MAX_LIMBS];
+    let mut sum_b = [0; MAX_LIMBS];
+    sum_a[..half].copy_from_slice(&a[..half]);
+    sum_b[..half].copy_from_slice(&b[..half]);
+    for i in 0..rest {
+        sum_a[i] ^= a[half + i];
+        sum_b[i] ^= b[half + i];
+    }
+
+    let mut middle = [0; MAX_LIMBS * 2];
+    karatsuba(&sum_a[..half], &sum_b[..half], &mut middle[..half * 2]);
+    karatsuba(&a[..half], &b[..half], &mut out[..half * 2]);
+    karatsuba(&a[half..], &b[half..], &mut out[half * 2..]);
+
+    for i in 0..(half * 2) {
+        middle[i] ^= out[i];
+    }
+    for i in 0..(rest * 2) {
+        middle[i] ^= out[half * 2 + i];
+    }
+    for i in 0..(half * 2) {
+        out[half + i] ^= middle[i];
+    }
+}
+
+/* Product of the first size limbs of a and b. */
+fn mul_limbs(a: &Limbs, b: &Limbs, size: usize) -> LimbsWide {
+    let mut result = [0; MAX_LIMBS * 2 + 1];
+    karatsuba(&a[..size], &b[..size], &mut result[..size * 2]);
+    return result;
+}
+
+/* Low half of value with a zero inserted after each bit. */
+#[inline]
+fn spread_u32(value: u64) -> u64 {
+    let mut x = value & 0x00_00_00_00_FF_FF_FF_FF;
+    x = (x | (x << 16)) & 0x00_00_FF_FF_00_00_FF_FF;
+    x = (x | (x << 8)) & 0x00_FF_00_FF_00_FF_00_FF;
+    x = (x | (x << 4)) & 0x0F_0F_0F_0F_0F_0F_0F_0F;
+    x = (x | (x << 2)) & 0x33_33_33_33_33_33_33_33;
+    x = (x | (x << 1)) & 0x55_55_55_55_55_55_55_55;
+    return x;
+}
+
+fn sqr_limbs(a: &Limbs, size: usize) -> LimbsWide {
+    let mut result = [0; MAX_LIMBS * 2 + 1];
+    for i in 0..size {
+        result[i * 2] = spread_u32(a[i]);
+        result[i * 2 + 1] = spread_u32(a[i] >> 32);
+    }
+    return result;
+}
+
+#[inline]
+fn xor_shifted(value: &mut [u64], word: u64, bit: usize) {
+    let pos = bit / WORD_SIZE;
+    let shift = bit % WORD_SIZE;
+    value[pos] ^= word << shift;
+    if shift > 0 {
+        value[pos + 1] ^= word >> (WORD_SIZE - shift);
+    }
+}
+
+/* gf2m::reduce_sparse_words on limbs, the first len limbs of
+   value are folded below x^m in place. */
+fn reduce_sparse_limbs(value: &mut LimbsWide, len: usize, field_m: usize, terms: &[usize; 4]) {
+    let top_limb = field_m / WORD_SIZE;
+    let top_bit = field_m % WORD_SIZE;
+
+    let mut i = len;
+    while i > top_limb + 1 || (i > top_limb && top_bit == 0) {
+        i -= 1;
+        let word = value[i];
+        value[i] = 0;
+
+        let offset = i * WORD_SIZE - field_m;
+        xor_shifted(value, word, offset);
+        for k in 1..terms.len() {
+            if terms[k] != 0 {
+                xor_shifted(value, word, offset + terms[k]);
+            }
+        }
+    }
+
+    if top_bit > 0 && top_limb < len {
+        let word = value[top_limb] >> top_bit;
+        value[top_limb] &= (1 << top_bit) - 1;
+
+        xor_shifted(value, word, 0);
+        for k in 1..terms.len() {
+            if terms[k] != 0 {
+                xor_shifted(value, word, terms[k]);
+            }
+        }
+    }
+}
+
+/* Whether mul_sparse and sqr_sparse take this modulus: the middle
+   terms have to be a limb away from the top, as gf2m::sparse_terms
+   asks a word away for 32-bit folding. */
+pub fn fits_sparse(field_m: usize, k1: usize) -> bool {
+    return field_m >= k1 + WORD_SIZE && limb_count(gf2m::field_words(field_m)) <= MAX_LIMBS;
+}
+
+/* value_a * value_b mod x^m + x^k1 + x^k2 + x^k3 + 1 without leaving
+   64-bit limbs: operands are converted once, multiplied and folded
+   as limbs, and the result converted back once. See fits_sparse. */
+pub fn mul_sparse<const N: usize>(value_a: &[u32; N], value_b: &[u32; N], field_m: usize,
+                                  k1: usize, k2: usize, k3: usize) -> [u32; N] {
+    let size = limb_count(N);
+    let mut product = mul_limbs(&to_limbs(value_a), &to_limbs(value_b), size);
+    reduce_sparse_limbs(&mut product, size * 2, field_m, &[0, k1, k2, k3]);
+    let mut ret = [0; N];
+    from_limbs(&product, &mut ret);
+    return ret;
+}
+
+/* Same for squaring, the bit spread goes by shifts and masks. */
+pub fn sqr_sparse<const N: usize>(value: &[u32; N], field_m: usize,
+                                  k1: usize, k2: usize, k3: usize) -> [u32; N] {
+    let size = limb_count(N);
+    let mut product = sqr_limbs(&to_limbs(value), size);
+    reduce_sparse_limbs(&mut product, size * 2, field_m, &[0, k1, k2, k3]);
+    let mut ret = [0; N];
+    from_limbs(&product, &mut ret);
+    return ret;
+}
+
+/* out = value_a * value_b on limbs for slices of up to
+   gf2m::MAX_WORDS words, out has twice that length. */
+pub fn mul_slices(value_a: &[u32], value_b: &[u32], out: &mut [u32]) {
+    assert!(value_a.len() == value_b.len() && value_a.len() <= gf2m::MAX_WORDS);
+    assert!(out.len() == value_a.len() * 2);
+    let product = mul_limbs(&to_limbs(value_a), &to_limbs(value_b), limb_count(value_a.len()));
+    from_limbs(&product, out);
+}
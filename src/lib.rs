@@ -1,5 +1,25 @@
+// explicit returns, field: field initializers and indexed loops
+// are the house style, the matching clippy lints are off
+#![allow(clippy::needless_return, clippy::redundant_field_names,
+         clippy::needless_range_loop, clippy::assign_op_pattern,
+         clippy::needless_late_init, clippy::bool_assert_comparison,
+         clippy::bool_comparison, clippy::question_mark,
+         clippy::single_match, clippy::manual_swap, clippy::clone_on_copy)]
+
+extern crate rand_core;
+#[cfg(test)]
+extern crate rand_chacha;
+
+pub mod clmul;
+pub mod ct;
+pub mod field;
+pub mod poly;
+pub mod onb;
 pub mod gf2m;
+pub mod gf2m64;
 pub mod curve;
+pub mod bitslice;
+pub mod random;
 pub mod dstu4145;
 pub mod dstu_params;
 
@@ -10,8 +30,23 @@ mod test {
     use curve::Point;
     use super::dstu_params;
     use super::dstu4145;
+    use super::clmul;
+    use super::ct;
+    use super::field;
+    use super::field::{BinaryField, FieldBackend, Inv, Square};
     use super::gf2m;
+    use super::gf2m64;
+    use super::poly;
+    use super::onb::{BasisConversion, OnbField};
     use super::curve;
+    use super::curve::Curve;
+    use super::bitslice;
+    use super::bitslice::BatchField;
+    use super::random;
+    use std::cell::RefCell;
+    use std::cmp::Ordering;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
 
     /*
     #[test]
@@ -35,13 +70,13 @@ mod test {
     fn test_dstu4145_verify_helper() {
         let curve = dstu_params::curve_257();
 
-        let s = gf2m::parse_hex(b"0CCC6816453A903A1B641DF999011177DF420D21A72236D798532AEF42E224AB");
-        let r = gf2m::parse_hex(b"491FA1EF75EAEF75E1F20CF3918993AB37E06005EA8E204BC009A1FA61BB0FB2");
-        let to_be_signed = gf2m::parse_hex(b"6845214B63288A832A772E1FE6CB6C7D3528569E29A8B3584370FDC65F474242");
+        let s = gf2m::parse_hex_words(b"0CCC6816453A903A1B641DF999011177DF420D21A72236D798532AEF42E224AB");
+        let r = gf2m::parse_hex_words(b"491FA1EF75EAEF75E1F20CF3918993AB37E06005EA8E204BC009A1FA61BB0FB2");
+        let to_be_signed = gf2m::parse_hex_words(b"6845214B63288A832A772E1FE6CB6C7D3528569E29A8B3584370FDC65F474242");
 
         let pubkey = Point {
-            x: gf2m::parse_hex(b"aff3ee09cb429284985849e20de5742e194aa631490f62ba88702505629a6589"),
-            y: gf2m::parse_hex(b"1b345bc134f27da251edfae97b3f306b4e8b8cb9cf86d8651e4fb301ef8e1239c")
+            x: gf2m::parse_hex_words(b"aff3ee09cb429284985849e20de5742e194aa631490f62ba88702505629a6589"),
+            y: gf2m::parse_hex_words(b"1b345bc134f27da251edfae97b3f306b4e8b8cb9cf86d8651e4fb301ef8e1239c")
         };
 
         assert_eq!(
@@ -55,6 +90,82 @@ mod test {
     }
 
 
+    #[test]
+    fn test_dstu4145_verify_range() {
+        let curve = dstu_params::curve_257();
+
+        let s = gf2m::parse_hex_words(b"0CCC6816453A903A1B641DF999011177DF420D21A72236D798532AEF42E224AB");
+        let r = gf2m::parse_hex_words(b"491FA1EF75EAEF75E1F20CF3918993AB37E06005EA8E204BC009A1FA61BB0FB2");
+        let to_be_signed = gf2m::parse_hex_words(b"6845214B63288A832A772E1FE6CB6C7D3528569E29A8B3584370FDC65F474242");
+        let pubkey = Point {
+            x: gf2m::parse_hex_words(b"aff3ee09cb429284985849e20de5742e194aa631490f62ba88702505629a6589"),
+            y: gf2m::parse_hex_words(b"1b345bc134f27da251edfae97b3f306b4e8b8cb9cf86d8651e4fb301ef8e1239c")
+        };
+
+        // s + n is the same scalar, but out of range
+        let mut s_big = s;
+        let mut carry = 0u64;
+        for i in 0..s.len() {
+            let sum = s[i] as u64 + curve.order()[i] as u64 + carry;
+            s_big[i] = sum as u32;
+            carry = sum >> 32;
+        }
+        assert!(!dstu4145::verify_helper(&pubkey, &s_big, &r, &to_be_signed, &curve));
+        assert!(!dstu4145::verify_helper(&pubkey, curve.order(), &r, &to_be_signed, &curve));
+        assert!(!dstu4145::verify_helper(&pubkey, &s, &[0; 9], &to_be_signed, &curve));
+    }
+
+    #[test]
+    fn test_curve_valid_public() {
+        let curve = dstu_params::curve_257();
+        let pubkey = Point {
+            x: gf2m::parse_hex_words(b"aff3ee09cb429284985849e20de5742e194aa631490f62ba88702505629a6589"),
+            y: gf2m::parse_hex_words(b"1b345bc134f27da251edfae97b3f306b4e8b8cb9cf86d8651e4fb301ef8e1239c")
+        };
+        assert!(curve::is_valid_public(&pubkey, &curve));
+
+        let mut off_curve = pubkey.clone();
+        off_curve.y[0] ^= 1;
+        // (0, sqrt(b)) is on the curve but has order 2
+        let order2 = curve::point_expand(&[0; 9], &curve).unwrap();
+        assert!(curve::is_on_curve(&order2, &curve));
+        let mut unreduced = pubkey.clone();
+        unreduced.x = gf2m::add_words(&unreduced.x, curve.field().modulus());
+
+        for point in [off_curve, order2, unreduced, curve::infinity()].iter() {
+            assert!(!curve::is_valid_public(point, &curve));
+        }
+    }
+
+    #[test]
+    fn test_ct_cmp() {
+        let mut state = 0xC3B;
+        let order = *dstu_params::curve_257().order();
+        let mut above = order;
+        above[0] += 1;
+        let mut below = order;
+        below[0] -= 1;
+
+        assert_eq!(ct::cmp(&order, &order), Ordering::Equal);
+        assert_eq!(ct::cmp(&below, &order), Ordering::Less);
+        assert_eq!(ct::cmp(&above, &order), Ordering::Greater);
+        assert!(ct::lt(&[0; 9], &gf2m::one_words()) && ct::gt(&gf2m::one_words(), &[0; 9]));
+        assert!(!ct::lt(&order, &order) && !ct::gt(&order, &order));
+
+        for _ in 0..16 {
+            let value_a = random_field(&mut state, 257);
+            let value_b = random_field(&mut state, 257);
+            let expect = value_a.iter().rev().cmp(value_b.iter().rev());
+            assert_eq!(ct::cmp(&value_a, &value_b), expect);
+        }
+
+        assert!(ct::in_range(&gf2m::one_words(), &order));
+        assert!(ct::in_range(&below, &order));
+        assert!(!ct::in_range(&[0; 9], &order));
+        assert!(!ct::in_range(&order, &order));
+        assert!(!ct::in_range(&above, &order));
+    }
+
     #[test]
     fn test_compute_modulus () {
         let mod257 = gf2m::compute_modulus(257, 12, 0, 0);
@@ -63,6 +174,48 @@ mod test {
         assert_eq!(mod431, gf2m::parse_hex(b"80000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000002b"));
     }
 
+    #[test]
+    fn test_field_parse_strict() {
+        let hex = b"1b345bc134f27da251edfae97b3f306b4e8b8cb9cf86d8651e4fb301ef8e1239c";
+        assert_eq!(gf2m::try_parse_hex(hex, 257), Ok(gf2m::parse_hex(hex)));
+        assert_eq!(gf2m::try_parse_hex(b"0001F", 5), Ok(gf2m::parse_hex(b"1f")));
+        assert_eq!(gf2m::try_parse_hex(b"20", 5), Err(gf2m::ParseError::OutOfRange));
+        assert_eq!(gf2m::try_parse_hex(b"12 34", 257), Err(gf2m::ParseError::InvalidDigit(2)));
+        assert_eq!(gf2m::try_parse_hex(b"", 257), Err(gf2m::ParseError::Empty));
+        assert_eq!(gf2m::try_parse_hex(&[b'0'; 129], 257), Err(gf2m::ParseError::TooLong));
+
+        let bytes = gf2m::to_bytes_le(&gf2m::parse_hex(hex));
+        assert_eq!(gf2m::try_from_bytes_le(&bytes, 257), Ok(gf2m::parse_hex(hex)));
+        assert_eq!(gf2m::try_from_bytes_le(&bytes, 256), Err(gf2m::ParseError::OutOfRange));
+        assert_eq!(gf2m::try_from_bytes_le(&[0; 65], 257), Err(gf2m::ParseError::TooLong));
+    }
+
+    #[test]
+    fn test_field_bytes() {
+        let mut state = 0xB17E5;
+        for &(m, k1, k2, k3) in dstu_params::FIELD_POLYNOMIALS.iter() {
+            let field = BinaryField::new(m, k1, k2, k3).unwrap();
+            let value = random_field(&mut state, m);
+
+            let le = field.to_bytes_le(&value);
+            let be = field.to_bytes_be(&value);
+            assert_eq!(le.len(), m.div_ceil(8));
+            assert_eq!(be.len(), le.len());
+            assert_eq!(be[0], le[le.len() - 1]);
+            assert_eq!(field.from_bytes_le(&le), Ok(value));
+            assert_eq!(field.from_bytes_be(&be), Ok(value));
+            assert_eq!(field.from_hex(field.to_hex(&value).as_bytes()), Ok(value));
+        }
+
+        let field = BinaryField::new(257, 12, 0, 0).unwrap();
+        let value = gf2m::parse_hex(b"1b345bc134f27da251edfae97b3f306b4e8b8cb9cf86d8651e4fb301ef8e1239c");
+        assert_eq!(gf2m::to_hex(&value), "1b345bc134f27da251edfae97b3f306b4e8b8cb9cf86d8651e4fb301ef8e1239c");
+        assert_eq!(gf2m::to_hex(&gf2m::zero()), "0");
+        assert_eq!(gf2m::to_hex(&[]), "0");
+        assert_eq!(field.to_bytes_be(&value)[..3], [0x01, 0xb3, 0x45]);
+        assert_eq!(field.from_bytes_be(&[0x02; 33]), Err(gf2m::ParseError::OutOfRange));
+    }
+
     #[test]
     fn test_field_add() {
         let value_a = gf2m::parse_hex(b"aff3ee09cb429284985849e20de5742e194aa631490f62ba88702505629a65890");
@@ -111,6 +264,23 @@ mod test {
         assert_eq!(shifted, expect);
     }
 
+    #[test]
+    fn test_field_mod_sparse() {
+        let mut state = 0x9E3779B97F4A7C15;
+        for &(m, k1, k2, k3) in dstu_params::FIELD_POLYNOMIALS.iter() {
+            let modulus = gf2m::compute_modulus(m, k1, k2, k3);
+            assert_eq!(gf2m::sparse_terms(&modulus), Some((m, k1, k2, k3)));
+
+            for _ in 0..8 {
+                let value = gf2m::mul(&random_field(&mut state, m), &random_field(&mut state, m));
+                assert_eq!(
+                    gf2m::reduce_sparse(&value, m, k1, k2, k3),
+                    gf2m::reduce_bitwise(&value, &modulus)
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_field_mul() {
         let mod257 = gf2m::compute_modulus(257, 12, 0, 0);
@@ -147,6 +317,191 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_field_mul_karatsuba() {
+        let mut state = 0x4A7;
+        for &(m, _, _, _) in dstu_params::FIELD_POLYNOMIALS.iter() {
+            let value_a = random_field(&mut state, m);
+            let value_b = random_field(&mut state, m);
+            assert_eq!(gf2m::mul_karatsuba(&value_a, &value_b), gf2m::mul_testbit(&value_a, &value_b));
+        }
+        let value_a = random_field(&mut state, 512);
+        let value_b = random_field(&mut state, 33);
+        assert_eq!(gf2m::mul_karatsuba(&value_a, &value_b), gf2m::mul_testbit(&value_a, &value_b));
+        assert_eq!(gf2m::mul_karatsuba(&gf2m::zero(), &value_b), gf2m::mul_testbit(&gf2m::zero(), &value_b));
+    }
+
+    #[test]
+    fn test_field_mul_prepared() {
+        let mut state = 0xC0B;
+        for &(m, _, _, _) in dstu_params::FIELD_POLYNOMIALS.iter() {
+            let value_a = random_field(&mut state, m);
+            let value_b = random_field(&mut state, m);
+            let prepared = gf2m::PreparedMultiplicand::new(&value_b);
+            assert_eq!(prepared.mul_comb(&value_a).as_flattened(), gf2m::mul_testbit(&value_a, &value_b));
+        }
+        let value_a = random_field(&mut state, 512);
+        let prepared = gf2m::PreparedMultiplicand::new(&value_a);
+        assert_eq!(prepared.mul_comb(&value_a).as_flattened(), gf2m::mul_testbit(&value_a, &value_a));
+        assert_eq!(prepared.mul_comb(&gf2m::zero()), [[0; 16]; 2]);
+        assert_eq!(prepared.mul(&value_a).as_flattened(), gf2m::mul_testbit(&value_a, &value_a));
+    }
+
+    #[test]
+    fn test_field_mul_acc() {
+        let mut state = 0xACC;
+        let field = BinaryField::new(431, 5, 3, 1).unwrap();
+        let mut acc = [[0; 16]; 2];
+        let mut expect = gf2m::zero();
+        for _ in 0..5 {
+            let value_a = random_field(&mut state, 431);
+            let value_b = random_field(&mut state, 431);
+            field.mul_acc(&mut acc, &value_a, &value_b);
+            field.sqr_acc(&mut acc, &value_a);
+            let prepared = FieldBackend::prepare(&field, &value_b);
+            FieldBackend::mul_prepared_acc(&field, &mut acc, &prepared, &value_b);
+            expect = field.add(&expect, &field.mul(&value_a, &value_b));
+            expect = field.add(&expect, &field.sqr(&value_a));
+            expect = field.add(&expect, &field.sqr(&value_b));
+        }
+        assert_eq!(field.reduce(acc.as_flattened()), expect);
+    }
+
+    #[test]
+    fn test_field64_mul() {
+        let mod257 = gf2m64::from_field32(&gf2m::compute_modulus(257, 12, 0, 0));
+        let value_a = gf2m64::from_field32(&gf2m::parse_hex(b"aff3ee09cb429284985849e20de5742e194aa631490f62ba88702505629a65890"));
+        let value_b = gf2m64::from_field32(&gf2m::parse_hex(b"a3391f6f341d627ab958fc4223ee8871e336c8d9dda30f407c369268363f0cccb"));
+
+        assert_eq!(
+            gf2m64::to_field32(&gf2m64::reduce(&gf2m64::mul(&value_a, &value_b), &mod257)),
+            gf2m::parse_hex(b"beb7d8390bb24fcf6882086cddd4ebe5270c1ed345bc516b40efb92b44530d5f")
+        );
+    }
+
+    #[test]
+    fn test_field64_mul_3x3() {
+        let value_a = gf2m::parse_hex(b"7a2c1d9f0e4b3a8857c6d5e4f3a2b1c0d9e8f7a6b5c4d3e2");
+        let value_b = gf2m::parse_hex(b"f1e2d3c4b5a69788796a5b4c3d2e1f00e1d2c3b4a5968778");
+
+        assert_eq!(
+            gf2m64::mul_field32(&value_a, &value_b),
+            gf2m::mul_testbit(&value_a, &value_b)
+        );
+    }
+
+    #[test]
+    fn test_field64_reduce() {
+        let mod257 = gf2m::compute_modulus(257, 12, 0, 0);
+        let value_a = gf2m::parse_hex(b"aff3ee09cb429284985849e20de5742e194aa631490f62ba88702505629a65890");
+
+        assert_eq!(
+            gf2m64::reduce_field32(&value_a, &mod257),
+            gf2m::parse_hex(b"ff3ee09cb429284985849e20de5742e194aa631490f62ba88702505629a60895")
+        );
+    }
+
+    #[test]
+    fn test_field64_sparse() {
+        fn check<const N: usize>(terms: (usize, usize, usize, usize), state: &mut u64) {
+            let (m, k1, k2, k3) = terms;
+            for _ in 0..4 {
+                let value_a: [u32; N] = random_words(state, m);
+                let value_b: [u32; N] = random_words(state, m);
+                let product = gf2m::mul_wide(&value_a, &value_b);
+                let square = gf2m::mul_wide(&value_a, &value_a);
+
+                let mut out = [[0; N]; 2];
+                gf2m64::mul_slices(&value_a, &value_b, out.as_flattened_mut());
+                assert_eq!(out, product);
+                assert_eq!(
+                    gf2m64::mul_sparse(&value_a, &value_b, m, k1, k2, k3),
+                    gf2m::reduce_sparse_words::<N>(product.as_flattened(), m, k1, k2, k3)
+                );
+                assert_eq!(
+                    gf2m64::sqr_sparse(&value_a, m, k1, k2, k3),
+                    gf2m::reduce_sparse_words::<N>(square.as_flattened(), m, k1, k2, k3)
+                );
+                assert_eq!(
+                    gf2m::mul_sparse_words(&value_a, &value_b, m, k1, k2, k3),
+                    gf2m64::mul_sparse(&value_a, &value_b, m, k1, k2, k3)
+                );
+                assert_eq!(
+                    gf2m::sqr_sparse_words(&value_a, m, k1, k2, k3),
+                    gf2m64::sqr_sparse(&value_a, m, k1, k2, k3)
+                );
+            }
+        }
+
+        let mut state = 0x6464;
+        // three limbs, odd limb counts and the widest field
+        check::<{ gf2m::field_words(163) }>((163, 7, 6, 3), &mut state);
+        check::<{ gf2m::field_words(257) }>((257, 12, 0, 0), &mut state);
+        check::<{ gf2m::field_words(431) }>((431, 5, 3, 1), &mut state);
+        check::<{ gf2m::field_words(571) }>((571, 10, 5, 2), &mut state);
+        assert!(!gf2m64::fits_sparse(67, 5));
+    }
+
+    fn xorshift(state: &mut u64) -> u32 {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        return (*state >> 32) as u32;
+    }
+
+    fn random_field(state: &mut u64, field_m: usize) -> gf2m::Field {
+        let mut ret = gf2m::zero();
+        for i in 0..field_m.div_ceil(32) {
+            ret[i] = xorshift(state);
+        }
+        return gf2m::truncate(&ret, field_m + 1);
+    }
+
+    fn random_words<const N: usize>(state: &mut u64, field_m: usize) -> [u32; N] {
+        let mut ret = [0; N];
+        for i in 0..field_m.div_ceil(32) {
+            ret[i] = xorshift(state);
+        }
+        return gf2m::truncate_words(&ret, field_m + 1);
+    }
+
+    #[test]
+    fn test_field_mul_clmul() {
+        let mut state = 0x2545F4914F6CDD1D;
+        for &(m, k1, k2, k3) in [(257, 12, 0, 0), (431, 5, 3, 1)].iter() {
+            let modulus = gf2m::compute_modulus(m, k1, k2, k3);
+            for _ in 0..32 {
+                let value_a = random_field(&mut state, m);
+                let value_b = random_field(&mut state, m);
+
+                assert_eq!(
+                    gf2m::reduce(&gf2m::mul_fast(&value_a, &value_b), &modulus),
+                    gf2m::reduce(&gf2m::mul(&value_a, &value_b), &modulus)
+                );
+                assert_eq!(gf2m::sqr_fast(&value_a), gf2m::sqr(&value_a));
+
+                if clmul::is_available() {
+                    assert_eq!(clmul::mul(&value_a, &value_b).unwrap(), gf2m::mul(&value_a, &value_b));
+                    assert_eq!(clmul::sqr(&value_a).unwrap(), gf2m::sqr(&value_a));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_field_sqr() {
+        let mut state = 0x0123456789ABCDEF;
+        for &(m, k1, k2, k3) in [(257, 12, 0, 0), (431, 5, 3, 1)].iter() {
+            let modulus = gf2m::compute_modulus(m, k1, k2, k3);
+            for _ in 0..16 {
+                let value = random_field(&mut state, m);
+                assert_eq!(gf2m::sqr(&value), gf2m::mul_testbit(&value, &value));
+                // Frobenius map has order m
+                assert_eq!(gf2m::sqr_n(&value, m, &modulus), value);
+            }
+        }
+    }
+
     #[test]
     fn test_field_neg() {
         let mod257 = gf2m::compute_modulus(257, 12, 0, 0);
@@ -158,6 +513,231 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_field_sqrt_pow() {
+        let mut state = 0x5_0_2;
+        for &(m, k1, k2, k3) in dstu_params::FIELD_POLYNOMIALS.iter() {
+            let field = BinaryField::new(m, k1, k2, k3).unwrap();
+            let modulus = field.modulus();
+            let value = random_field(&mut state, m);
+
+            let root = gf2m::sqrt(&value, modulus);
+            assert_eq!(field.sqr(&root), value);
+            assert_eq!(field.sqrt(&value), root);
+            assert_eq!(gf2m::frobenius(&value, m, modulus), value);
+            assert_eq!(gf2m::frobenius(&value, 3, modulus), gf2m::sqr_n(&value, 3, modulus));
+
+            // a^(2^m - 1) = 1
+            let mut order = gf2m::zero();
+            for i in 0..m {
+                order[i / 32] |= 1 << (i % 32);
+            }
+            assert_eq!(gf2m::pow(&value, &order, modulus), gf2m::one());
+            assert_eq!(gf2m::pow(&value, &[3], modulus), field.mul(&field.sqr(&value), &value));
+            assert_eq!(gf2m::pow(&value, &[0], modulus), gf2m::one());
+        }
+    }
+
+    #[test]
+    fn test_field_inv() {
+        let mod257 = gf2m::compute_modulus(257, 12, 0, 0);
+        let value_a = gf2m::parse_hex(b"aff3ee09cb429284985849e20de5742e194aa631490f62ba88702505629a65890");
+
+        assert_eq!(
+            gf2m::inv(&value_a, &mod257),
+            gf2m::parse_hex(b"f5ae84d0c4dc2e7e89c670fb2083d124be50b413efb6863705bd63a5168352e0")
+        );
+        assert_eq!(gf2m::inv(&gf2m::zero(), &mod257), gf2m::zero());
+
+        let mod1 = gf2m::parse_hex(b"3");
+        assert_eq!(gf2m::inv(&gf2m::one(), &mod1), gf2m::one());
+        assert_eq!(gf2m::inv(&gf2m::one(), &mod1), gf2m::neg(&gf2m::one(), &mod1));
+    }
+
+    #[test]
+    fn test_field_inv_neg() {
+        let mut state = 0x5DEECE66D;
+        for &(m, k1, k2, k3) in dstu_params::FIELD_POLYNOMIALS.iter() {
+            let modulus = gf2m::compute_modulus(m, k1, k2, k3);
+            for _ in 0..4 {
+                let value = random_field(&mut state, m);
+                let inverse = gf2m::inv(&value, &modulus);
+
+                assert_eq!(inverse, gf2m::neg(&value, &modulus));
+                assert_eq!(gf2m::reduce(&gf2m::mul(&inverse, &value), &modulus), gf2m::one());
+            }
+        }
+    }
+
+    #[test]
+    fn test_field_batch_invert() {
+        let mut state = 0xDEADBEEFCAFE;
+        let mod431 = gf2m::compute_modulus(431, 5, 3, 1);
+        let mut values: Vec<gf2m::Field> = (0..8).map(|_| random_field(&mut state, 431)).collect();
+        values[2] = gf2m::zero();
+        // unreduced zero and unreduced non-zero value
+        values[5] = mod431;
+        values[6] = gf2m::add(&values[6], &mod431);
+        let expect: Vec<gf2m::Field> = values.iter().map(|value| {
+            let value_r = gf2m::reduce(value, &mod431);
+            if gf2m::is_zero(&value_r) {
+                *value
+            } else {
+                gf2m::neg(&value_r, &mod431)
+            }
+        }).collect();
+
+        let zeros = gf2m::batch_invert(&mut values, &mod431);
+        assert_eq!(zeros, vec![2, 5]);
+        assert_eq!(values, expect);
+        assert_eq!(values[5], mod431);
+
+        let mut empty: Vec<gf2m::Field> = Vec::new();
+        assert_eq!(gf2m::batch_invert(&mut empty, &mod431), vec![]);
+    }
+
+    #[test]
+    fn test_ct_field() {
+        let mut state = 0xC0FFEE;
+        let mod431 = gf2m::compute_modulus(431, 5, 3, 1);
+        // dense modulus takes generic reduction path
+        let dense = gf2m::add(&mod431, &gf2m::compute_modulus(300, 200, 100, 50));
+
+        for _ in 0..8 {
+            let value_a = random_field(&mut state, 431);
+            let value_b = random_field(&mut state, 431);
+            let product = gf2m::mul(&value_a, &value_b);
+
+            assert_eq!(ct::reduce(&product, &mod431), gf2m::reduce_bitwise(&product, &mod431));
+            assert_eq!(ct::reduce(&product, &dense), gf2m::reduce_bitwise(&product, &dense));
+            assert_eq!(ct::inv(&value_a, &mod431), gf2m::inv(&value_a, &mod431));
+            assert_eq!(ct::truncate(&value_a, 257), gf2m::truncate(&value_a, 257));
+            assert_eq!(ct::truncate(&value_a, 32), gf2m::truncate(&value_a, 32));
+            assert_eq!(ct::eq(&value_a, &value_b), false);
+            assert_eq!(ct::eq(&value_a, &value_a), true);
+            assert_eq!(ct::select(&value_a, &value_b, 0), value_a);
+            assert_eq!(ct::select(&value_a, &value_b, 1), value_b);
+
+            let mut swap_a = value_a;
+            let mut swap_b = value_b;
+            ct::cswap(&mut swap_a, &mut swap_b, 0);
+            assert_eq!((swap_a, swap_b), (value_a, value_b));
+            ct::cswap(&mut swap_a, &mut swap_b, 1);
+            assert_eq!((swap_a, swap_b), (value_b, value_a));
+        }
+
+        assert_eq!(ct::is_zero(&gf2m::zero()), true);
+        assert_eq!(ct::is_zero(&gf2m::one()), false);
+        assert_eq!(ct::inv(&gf2m::zero(), &mod431), gf2m::zero());
+    }
+
+    /* BinaryField that logs every operation it is asked for. */
+    struct TracedField {
+        inner: BinaryField<9>,
+        trace: RefCell<Vec<&'static str>>
+    }
+
+    impl TracedField {
+        fn log(&self, op: &'static str) {
+            self.trace.borrow_mut().push(op);
+        }
+    }
+
+    impl FieldBackend<9> for TracedField {
+        type Prepared = gf2m::PreparedMultiplicand<9>;
+
+        fn degree(&self) -> usize {
+            return self.inner.degree();
+        }
+        fn unity(&self) -> [u32; 9] {
+            self.log("unity");
+            return self.inner.unity();
+        }
+        fn reduce(&self, value: &[u32]) -> [u32; 9] {
+            self.log("reduce");
+            return self.inner.reduce(value);
+        }
+        fn add(&self, value_a: &[u32; 9], value_b: &[u32; 9]) -> [u32; 9] {
+            self.log("add");
+            return self.inner.add(value_a, value_b);
+        }
+        fn mul(&self, value_a: &[u32; 9], value_b: &[u32; 9]) -> [u32; 9] {
+            self.log("mul");
+            return self.inner.mul(value_a, value_b);
+        }
+        fn sqr(&self, value: &[u32; 9]) -> [u32; 9] {
+            self.log("sqr");
+            return self.inner.sqr(value);
+        }
+        fn sqrt(&self, value: &[u32; 9]) -> [u32; 9] {
+            self.log("sqrt");
+            return self.inner.sqrt(value);
+        }
+        fn inv(&self, value: &[u32; 9]) -> [u32; 9] {
+            self.log("inv");
+            return self.inner.inv(value);
+        }
+        fn trace(&self, value: &[u32; 9]) -> u32 {
+            self.log("trace");
+            return self.inner.trace(value);
+        }
+        fn solve_quadratic(&self, beta: &[u32; 9]) -> Option<[u32; 9]> {
+            self.log("solve_quadratic");
+            return self.inner.solve_quadratic(beta);
+        }
+        fn prepare(&self, value: &[u32; 9]) -> Self::Prepared {
+            self.log("prepare");
+            return self.inner.prepare(value);
+        }
+        fn mul_prepared(&self, prepared: &Self::Prepared, value: &[u32; 9]) -> [u32; 9] {
+            self.log("mul_prepared");
+            return self.inner.mul_prepared(prepared, value);
+        }
+    }
+
+    /* point_mul_ct has to ask the field for the same sequence of
+       operations whatever the factor is, including 0, n - 1 and n. */
+    #[test]
+    fn test_point_mul_ct_trace() {
+        let params = dstu_params::curve_257();
+        let field = TracedField {
+            inner: BinaryField::new(257, 12, 0, 0).unwrap(),
+            trace: RefCell::new(Vec::new())
+        };
+        let curve = Curve::new(*params.param_a(), *params.param_b(), *params.order(), params.base().clone(), field);
+        let base = curve.base().clone();
+
+        let mut one = [0; 9];
+        one[0] = 1;
+        let (order_less, _) = ct::int_add(curve.order(), &[!0; 9]);
+        let mut state = 0x5EED;
+        let factors = [
+            [0; 9], one, order_less, *curve.order(),
+            random_words::<9>(&mut state, 200), random_words::<9>(&mut state, 255), [!0; 9]
+        ];
+
+        let mut traces = Vec::new();
+        for factor in factors.iter() {
+            curve.field().trace.borrow_mut().clear();
+            let result = curve::point_mul_ct(&base, factor, &curve);
+            assert_eq!(result, curve::point_mul(&base, factor, &params));
+            traces.push(curve.field().trace.replace(Vec::new()));
+        }
+
+        assert!(!traces[0].contains(&"inv"));
+        for trace in traces.iter() {
+            assert_eq!(trace, &traces[0]);
+        }
+
+        assert_eq!(curve::point_mul_ct(&base, &one, &curve), base);
+        let mut negated = base.clone();
+        for i in 0..9 {
+            negated.y[i] ^= base.x[i];
+        }
+        assert_eq!(curve::point_mul_ct(&base, &order_less, &curve), negated);
+        assert_eq!(curve::point_mul_ct(&base, curve.order(), &curve), curve::infinity());
+    }
+
     #[test]
     fn test_field_parsehex() {
         let value_a: gf2m::Field = [
@@ -168,68 +748,572 @@ mod test {
         assert_eq!(value_a, expect);
     }
 
+    #[test]
+    fn test_field_words_odd() {
+        let value_a = gf2m::parse_hex(b"1aff3ee09cb429284985849e20de5742e194aa631490f62ba88702505629a65890");
+        let value_b = gf2m::parse_hex(b"a3391f6f341d627ab958fc4223ee8871e336c8d9dda30f407c369268363f0cccb");
+        let mut short_a = [0; 9];
+        let mut short_b = [0; 9];
+        short_a.copy_from_slice(&value_a[..9]);
+        short_b.copy_from_slice(&value_b[..9]);
+
+        let result: [u32; 18] = gf2m::mul_words(&short_a, &short_b);
+        assert_eq!(result[..], gf2m::mul_testbit(&value_a, &value_b)[..18]);
+        let result: [u32; 18] = gf2m::mul_fast_words(&short_a, &short_b);
+        assert_eq!(result[..], gf2m::mul_testbit(&value_a, &value_b)[..18]);
+    }
+
+    #[test]
+    fn test_field_words_571() {
+        // sect571: x^571 + x^10 + x^5 + x^2 + 1, 18 words
+        let mut state = 0x571;
+        let mut modulus = [0u32; 18];
+        for &bit in [571, 10, 5, 2, 0].iter() {
+            modulus[bit / 32] |= 1 << (bit % 32);
+        }
+        let mut value = [0u32; 18];
+        for i in 0..18 {
+            value[i] = xorshift(&mut state);
+        }
+        value[17] &= (1 << 27) - 1;
+
+        let inverse = gf2m::neg_words(&value, &modulus);
+        let product: [u32; 36] = gf2m::mul_words(&value, &inverse);
+        let mut one = [0u32; 18];
+        one[0] = 1;
+        assert_eq!(gf2m::reduce_words(&product, &modulus), one);
+
+        let square: [u32; 36] = gf2m::sqr_fast_words(&value);
+        let product: [u32; 36] = gf2m::mul_fast_words(&value, &value);
+        assert_eq!(square[..], product[..]);
+    }
+
+    #[test]
+    fn test_binary_field_words() {
+        let mut state = 0x1DE7;
+        assert_eq!(gf2m::field_words(257), 9);
+        assert_eq!(BinaryField::<8>::new(257, 12, 0, 0), Err(field::FieldError::Degree));
+        let tight = BinaryField::<9>::new(257, 12, 0, 0).unwrap();
+        let full = BinaryField::<16>::new(257, 12, 0, 0).unwrap();
+        for _ in 0..8 {
+            let value_a = random_field(&mut state, 257);
+            let value_b = random_field(&mut state, 257);
+            let short_a = gf2m::resize_words(&value_a);
+            let short_b = gf2m::resize_words(&value_b);
+
+            assert_eq!(gf2m::resize_words(&tight.mul(&short_a, &short_b)), full.mul(&value_a, &value_b));
+            assert_eq!(gf2m::resize_words(&tight.sqr(&short_a)), full.sqr(&value_a));
+            assert_eq!(gf2m::resize_words(&tight.sqrt(&short_a)), full.sqrt(&value_a));
+            assert_eq!(gf2m::resize_words(&tight.inv(&short_a)), full.inv(&value_a));
+            assert_eq!(tight.trace(&short_a), full.trace(&value_a));
+            assert_eq!(
+                tight.half_trace(&short_a).map(|value| gf2m::resize_words(&value)),
+                full.half_trace(&value_a)
+            );
+        }
+    }
+
+    #[test]
+    fn test_field_trace_mask() {
+        for &(m, k1, k2, k3) in dstu_params::FIELD_POLYNOMIALS.iter() {
+            let modulus = gf2m::compute_modulus(m, k1, k2, k3);
+            let mask = gf2m::trace_mask(&modulus);
+
+            // Tr(x^i) = x^i + x^2i + ... + x^(2^(m-1) i), low and high powers only
+            let mut power = gf2m::one();
+            for i in 0..m {
+                if i < 24 || i + 8 >= m {
+                    let mut sum = power;
+                    let mut conj = power;
+                    for _ in 1..m {
+                        conj = gf2m::reduce(&gf2m::sqr(&conj), &modulus);
+                        sum = gf2m::add(&sum, &conj);
+                    }
+                    assert_eq!(gf2m::has_bit(&mask, i), sum[0] == 1, "m = {}, i = {}", m, i);
+                }
+                power = gf2m::reduce(&gf2m::shl(&power, 1), &modulus);
+            }
+        }
+    }
+
+    #[test]
+    fn test_field_solve_quadratic() {
+        let mut state = 0x50_1E;
+        let field = BinaryField::new(257, 12, 0, 0).unwrap();
+        let modulus = field.modulus();
+        let mut found = 0;
+        for _ in 0..16 {
+            let beta = random_field(&mut state, 257);
+            match field::solve_quadratic(&beta, &field) {
+                Some(z) => {
+                    assert_eq!(field.trace(&beta), 0);
+                    assert_eq!(gf2m::add(&field.sqr(&z), &z), beta);
+                    assert_eq!(z, gf2m::squad_odd(&beta, modulus, 257));
+                    found += 1;
+                },
+                None => assert_eq!(field.trace(&beta), 1),
+            }
+        }
+        assert!(found > 0 && found < 16);
+
+        // no half-trace for even m
+        let field = BinaryField::new(8, 4, 3, 1).unwrap();
+        assert_eq!(field.half_trace(&gf2m::one()), None);
+        assert_eq!(field::solve_quadratic(&gf2m::one(), &field), None);
+    }
+
+    #[test]
+    fn test_poly() {
+        // (x^2 + x + 1)(x^3 + x + 1) = x^5 + x^4 + 1
+        let a = poly::from_words(&[0b111]);
+        let b = poly::from_words(&[0b1011]);
+        let ab = poly::mul(&a, &b);
+        assert_eq!(ab, vec![0b110001]);
+        assert_eq!(poly::divmod(&ab, &a), (b.clone(), vec![]));
+        assert_eq!(poly::divmod(&poly::add(&ab, &[0b10]), &b), (a.clone(), vec![0b10]));
+        assert_eq!(poly::gcd(&ab, &poly::mul(&a, &a)), a);
+        assert!(poly::is_one(&poly::gcd(&a, &b)));
+
+        // x^(2^3 - 1) = 1 in GF(2^3)
+        assert_eq!(poly::powmod(&poly::x(), &[7], &b), poly::one());
+        assert!(poly::is_irreducible_rabin(&b));
+        assert!(poly::is_irreducible_ben_or(&b));
+        assert!(!poly::is_irreducible_rabin(&ab));
+        assert!(!poly::is_irreducible_ben_or(&ab));
+
+        for &(m, k1, k2, k3) in dstu_params::FIELD_POLYNOMIALS.iter() {
+            assert!(poly::is_irreducible_ben_or(&poly::from_terms(m, k1, k2, k3)));
+        }
+        // AES polynomial, x^8 + x^4 + x^3 + x + 1
+        assert!(poly::is_irreducible_rabin(&poly::from_terms(8, 4, 3, 1)));
+        assert!(!poly::is_irreducible_ben_or(&poly::from_terms(163, 7, 6, 2)));
+
+        assert_eq!(<BinaryField>::new(257, 11, 0, 0), Err(field::FieldError::Reducible));
+        assert_eq!(<BinaryField>::new(163, 7, 6, 2), Err(field::FieldError::Reducible));
+        assert_eq!(<BinaryField>::new(512, 12, 0, 0), Err(field::FieldError::Degree));
+        assert_eq!(<BinaryField>::new(257, 257, 0, 0), Err(field::FieldError::Degree));
+    }
+
+    #[test]
+    fn test_onb_field() {
+        let mut state = 0x0_4B;
+        // DSTU 4145 ONB degrees
+        for &m in [173, 179, 191, 233, 431].iter() {
+            let field = OnbField::new(m).unwrap();
+            let one = field.one();
+            for _ in 0..4 {
+                let value_a = random_field(&mut state, m);
+                let value_b = random_field(&mut state, m);
+                let value_c = random_field(&mut state, m);
+
+                assert_eq!(field.mul(&value_a, &one), value_a);
+                assert_eq!(field.mul(&value_a, &value_b), field.mul(&value_b, &value_a));
+                assert_eq!(field.sqr(&value_a), field.mul(&value_a, &value_a));
+                assert_eq!(
+                    field.mul(&field.mul(&value_a, &value_b), &value_c),
+                    field.mul(&value_a, &field.mul(&value_b, &value_c))
+                );
+                assert_eq!(
+                    field.mul(&value_a, &field.add(&value_b, &value_c)),
+                    field.add(&field.mul(&value_a, &value_b), &field.mul(&value_a, &value_c))
+                );
+                assert_eq!(field.mul(&field.inv(&value_a), &value_a), one);
+                assert_eq!(field.sqr(&field.sqrt(&value_a)), value_a);
+
+                // Tr(a) = a + a^2 + ... + a^(2^(m-1)) is 0 or 1
+                let mut sum = field.zero();
+                for i in 0..m {
+                    sum = field.add(&sum, &field.frobenius(&value_a, i));
+                }
+                assert_eq!(sum, if field.trace(&value_a) == 1 { one } else { field.zero() });
+
+                match field.solve_quadratic(&value_a) {
+                    Some(z) => assert_eq!(field.add(&field.sqr(&z), &z), value_a),
+                    None => assert_eq!(field.trace(&value_a), 1),
+                }
+            }
+        }
+        assert_eq!(<OnbField>::new(163), Err(field::FieldError::NoOptimalBasis));
+        assert!(<OnbField>::new(5).is_ok());
+    }
+
+    /* m = 173 at 6 words, values computed from the definition of the
+       basis: beta_i beta_j = beta_k + beta_l with 2^k = +-(2^i + 2^j)
+       and 2^l = +-(2^i - 2^j) mod p, independently of the convolution. */
+    #[test]
+    fn test_onb_known_answer() {
+        const WORDS: usize = gf2m::field_words(173);
+        let field = OnbField::<WORDS>::new(173).unwrap();
+        let value_a = gf2m::parse_hex_words(b"1b9c3f0e4a7d2c5b8e1f6a3d9c0b7e2f5a8d1c4b7e0f");
+        let value_b = gf2m::parse_hex_words(b"e5d2a7f1c4b8e3d6a9f0c2b5e8d1a4f7c0b3e6d9a2f");
+
+        assert_eq!(
+            field.mul(&value_a, &value_b),
+            gf2m::parse_hex_words(b"1d08c53c680f71013b136d5837503fb224571139c303")
+        );
+        assert_eq!(
+            field.sqr(&value_a),
+            gf2m::parse_hex_words(b"17387e1c94fa58b71c3ed47b3816fc5eb51a3896fc1f")
+        );
+        assert_eq!(
+            field.inv(&value_a),
+            gf2m::parse_hex_words(b"1d3b5d89986814c8756a1d8eeda79f7595bb2460af33")
+        );
+        assert_eq!(field.trace(&value_a), 1);
+        assert_eq!(field.trace(&value_b), 0);
+        assert_eq!(OnbField::<5>::new(173), Err(field::FieldError::Degree));
+    }
+
+    #[test]
+    fn test_onb_conversion() {
+        let mut state = 0xC0_4B;
+        for &(m, k1, k2, k3) in dstu_params::FIELD_POLYNOMIALS.iter() {
+            let onb = match OnbField::new(m) {
+                Ok(onb) => onb,
+                Err(_) => continue,
+            };
+            let field = BinaryField::new(m, k1, k2, k3).unwrap();
+            let conversion = BasisConversion::new(m, field.modulus()).unwrap();
+
+            assert_eq!(conversion.to_polynomial(&onb.one()), gf2m::one());
+            for _ in 0..4 {
+                let value_a = random_field(&mut state, m);
+                let value_b = random_field(&mut state, m);
+                let normal_a = conversion.to_normal(&value_a);
+                let normal_b = conversion.to_normal(&value_b);
+
+                assert_eq!(conversion.to_polynomial(&normal_a), value_a);
+                assert_eq!(conversion.to_normal(&conversion.to_polynomial(&value_b)), value_b);
+                assert_eq!(conversion.to_normal(&field.mul(&value_a, &value_b)), onb.mul(&normal_a, &normal_b));
+                assert_eq!(conversion.to_normal(&field.sqr(&value_a)), onb.sqr(&normal_a));
+                assert_eq!(onb.trace(&normal_a), field.trace(&value_a));
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_field() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0xD57);
+        let mut top = 0;
+        for &m in [163, 257, 431].iter() {
+            for _ in 0..32 {
+                let value: gf2m::Field = random::random_field(&mut rng, m);
+                assert!(gf2m::bit_size(&value) <= m);
+                if gf2m::bit_size(&value) == m {
+                    top += 1;
+                }
+                assert!(!gf2m::is_zero(&random::random_field_nonzero(&mut rng, m)));
+            }
+        }
+        assert!(top > 0);
+
+        let mut rng_a = ChaCha20Rng::seed_from_u64(1);
+        let mut rng_b = ChaCha20Rng::seed_from_u64(1);
+        let value_a: gf2m::Field = random::random_field(&mut rng_a, 257);
+        assert_eq!(value_a, random::random_field(&mut rng_b, 257));
+    }
+
+    #[test]
+    fn test_random_scalar() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0x5CA1A);
+        let curve = dstu_params::curve_257();
+        let order_size = gf2m::bit_size(curve.order());
+        for _ in 0..64 {
+            let value = random::random_scalar(&mut rng, curve.order());
+            assert!(!gf2m::is_zero_words(&value));
+            assert!(gf2m::bit_size(&value) <= order_size);
+            assert!(ct::lt(&value, curve.order()));
+        }
+
+        // [1, 2] only
+        let mut order = gf2m::zero();
+        order[0] = 3;
+        for _ in 0..16 {
+            let value = random::random_scalar(&mut rng, &order);
+            assert!(value[0] == 1 || value[0] == 2);
+        }
+    }
+
+    #[test]
+    fn test_binary_field() {
+        let mut state = 0xB1AB1A;
+        let field = BinaryField::new(431, 5, 3, 1).unwrap();
+        assert_eq!(field.terms(), (431, 5, 3, 1));
+        assert_eq!(*field.modulus(), gf2m::compute_modulus(431, 5, 3, 1));
+
+        for _ in 0..8 {
+            let value_a = random_field(&mut state, 431);
+            let value_b = random_field(&mut state, 431);
+
+            assert_eq!(field.trace(&value_a), gf2m::trace(&value_a, field.modulus()) & 1);
+            assert_eq!(field.mul(&value_a, &value_b), gf2m::reduce(&gf2m::mul(&value_a, &value_b), field.modulus()));
+            assert_eq!(field.mul(&field.inv(&value_a), &value_a), gf2m::one());
+        }
+    }
+
+    #[test]
+    fn test_field_element_ops() {
+        let field: BinaryField = BinaryField::new(257, 12, 0, 0).unwrap();
+        field.scope(|s| {
+            let value_a = s.element(&gf2m::parse_hex(b"aff3ee09cb429284985849e20de5742e194aa631490f62ba88702505629a65890"));
+            let value_b = s.element(&gf2m::parse_hex(b"a3391f6f341d627ab958fc4223ee8871e336c8d9dda30f407c369268363f0cccb"));
+
+            assert_eq!(
+                *(value_a * value_b).value(),
+                gf2m::parse_hex(b"beb7d8390bb24fcf6882086cddd4ebe5270c1ed345bc516b40efb92b44530d5f")
+            );
+            assert_eq!(
+                *value_a.inv().value(),
+                gf2m::parse_hex(b"f5ae84d0c4dc2e7e89c670fb2083d124be50b413efb6863705bd63a5168352e0")
+            );
+            assert_eq!(value_a * value_a.inv(), s.one());
+            assert_eq!(value_a.square(), value_a * value_a);
+            assert_eq!(value_a.sqrt().square(), value_a);
+            assert_eq!(value_a + value_a, s.zero());
+            assert_eq!(value_a - value_b, value_a + value_b);
+            assert_eq!(-value_a, value_a);
+
+            let mut acc = s.accumulator();
+            acc.mul(value_a, value_b);
+            acc.sqr(value_b);
+            assert_eq!(acc.reduce(), value_a * value_b + value_b.square());
+        });
+    }
+
+    #[test]
+    fn test_field_element_format() {
+        let field: BinaryField = BinaryField::new(257, 12, 0, 0).unwrap();
+        field.scope(|s| {
+            let value = s.element(&gf2m::parse_hex(b"00AFF3EE09CB429284985849E20DE5742E194AA631490F62BA88702505629A6589"));
+
+            assert_eq!(format!("{}", value), "aff3ee09cb429284985849e20de5742e194aa631490f62ba88702505629a6589");
+            assert_eq!(format!("{:X}", value), "AFF3EE09CB429284985849E20DE5742E194AA631490F62BA88702505629A6589");
+            assert_eq!(format!("{:?}", s.one()), "FieldElement(0x1)");
+            assert_eq!(format!("{:x}", s.zero()), "0");
+            assert_eq!(format!("{:#06x}", s.one()), "0x0001");
+        });
+    }
+
     #[test]
     fn test_point_double() {
-        let mod257 = gf2m::compute_modulus(257, 12, 0, 0);
-        let curve_a = gf2m::zero();
+        let curve = dstu_params::curve_257();
         let point = Point {
-            x: gf2m::parse_hex(b"00AFF3EE09CB429284985849E20DE5742E194AA631490F62BA88702505629A6589"),
-            y: gf2m::parse_hex(b"01B345BC134F27DA251EDFAE97B3F306B4E8B8CB9CF86D8651E4FB301EF8E1239C")
+            x: gf2m::parse_hex_words(b"00AFF3EE09CB429284985849E20DE5742E194AA631490F62BA88702505629A6589"),
+            y: gf2m::parse_hex_words(b"01B345BC134F27DA251EDFAE97B3F306B4E8B8CB9CF86D8651E4FB301EF8E1239C")
         };
 
         assert_eq!(
-            curve::point_dbl(&point, &mod257, &curve_a),
+            curve::point_dbl(&point, &curve),
             Point {
-                x: gf2m::parse_hex(b"176dbde19773dfd335665597e8d6a0ab678721a5bb7030f25dc4c48b809ef3520"),
-                y: gf2m::parse_hex(b"6e75301556ea5d571403086691030f024c026907c8e818b2eedd9184d12040ee")
+                x: gf2m::parse_hex_words(b"176dbde19773dfd335665597e8d6a0ab678721a5bb7030f25dc4c48b809ef3520"),
+                y: gf2m::parse_hex_words(b"6e75301556ea5d571403086691030f024c026907c8e818b2eedd9184d12040ee")
             }
         );
     }
 
     #[test]
     fn test_point_add() {
-        let curve_a = gf2m::zero();
-        let mod257 = gf2m::compute_modulus(257, 12, 0, 0);
+        let curve = dstu_params::curve_257();
 
         let point = Point {
-            x: gf2m::parse_hex(b"00AFF3EE09CB429284985849E20DE5742E194AA631490F62BA88702505629A6589"),
-            y: gf2m::parse_hex(b"01B345BC134F27DA251EDFAE97B3F306B4E8B8CB9CF86D8651E4FB301EF8E1239C")
+            x: gf2m::parse_hex_words(b"00AFF3EE09CB429284985849E20DE5742E194AA631490F62BA88702505629A6589"),
+            y: gf2m::parse_hex_words(b"01B345BC134F27DA251EDFAE97B3F306B4E8B8CB9CF86D8651E4FB301EF8E1239C")
         };
 
         let point_2 = Point {
-            x: gf2m::parse_hex(b"176dbde19773dfd335665597e8d6a0ab678721a5bb7030f25dc4c48b809ef3520"),
-            y: gf2m::parse_hex(b"6e75301556ea5d571403086691030f024c026907c8e818b2eedd9184d12040ee")
+            x: gf2m::parse_hex_words(b"176dbde19773dfd335665597e8d6a0ab678721a5bb7030f25dc4c48b809ef3520"),
+            y: gf2m::parse_hex_words(b"6e75301556ea5d571403086691030f024c026907c8e818b2eedd9184d12040ee")
         };
 
         assert_eq!(
-            curve::point_add(&point, &point_2, &mod257, &curve_a),
+            curve::point_add(&point, &point_2, &curve),
             Point {
-                x: gf2m::parse_hex(b"9a826cff814626da47bc409383d83922f65ec3e890e3b41a60e89f3a864c2766"),
-                y: gf2m::parse_hex(b"1e465ea7610428ec6b0b56be039dd73f3fe18d7d7731d60a18ff9224caaf43b76")
+                x: gf2m::parse_hex_words(b"9a826cff814626da47bc409383d83922f65ec3e890e3b41a60e89f3a864c2766"),
+                y: gf2m::parse_hex_words(b"1e465ea7610428ec6b0b56be039dd73f3fe18d7d7731d60a18ff9224caaf43b76")
             }
         );
     }
 
     #[test]
     fn test_point_mul() {
-        let mod257 = gf2m::compute_modulus(257, 12, 0, 0);
-        let curve_a = gf2m::zero();
-        let privd = gf2m::parse_hex(b"2A45EAFE4CD469F811737780C57253360FBCC58E134C9A1FDCD10B0E4529A143");
+        let curve = dstu_params::curve_257();
+        let privd = gf2m::parse_hex_words(b"2A45EAFE4CD469F811737780C57253360FBCC58E134C9A1FDCD10B0E4529A143");
 
         let point = Point {
-            x: gf2m::parse_hex(b"00AFF3EE09CB429284985849E20DE5742E194AA631490F62BA88702505629A6589"),
-            y: gf2m::parse_hex(b"01B345BC134F27DA251EDFAE97B3F306B4E8B8CB9CF86D8651E4FB301EF8E1239C")
+            x: gf2m::parse_hex_words(b"00AFF3EE09CB429284985849E20DE5742E194AA631490F62BA88702505629A6589"),
+            y: gf2m::parse_hex_words(b"01B345BC134F27DA251EDFAE97B3F306B4E8B8CB9CF86D8651E4FB301EF8E1239C")
         };
 
         assert_eq!(
-            curve::point_mul(&point, &privd, &mod257, &curve_a),
+            curve::point_mul(&point, &privd, &curve),
             Point {
-                x: gf2m::parse_hex(b"8c3d388b1c51116cf0ed041718309b360f775d8df86e9fc141822e79a3b0da8b"),
-                y: gf2m::parse_hex(b"a8624188d9f4ab0afafbde6230cd8cf7c28b38f42fcbb4021ff0c0244a5ddbbd")
+                x: gf2m::parse_hex_words(b"8c3d388b1c51116cf0ed041718309b360f775d8df86e9fc141822e79a3b0da8b"),
+                y: gf2m::parse_hex_words(b"a8624188d9f4ab0afafbde6230cd8cf7c28b38f42fcbb4021ff0c0244a5ddbbd")
             }
         );
     }
 
+    #[test]
+    fn test_point_mul_base() {
+        fn check<const N: usize>(curve: &Curve<N>, state: &mut u64) {
+            for _ in 0..2 {
+                let factor = random_words(state, 160);
+                assert_eq!(
+                    curve::point_mul_base(&factor, curve),
+                    curve::point_mul(curve.base(), &factor, curve)
+                );
+            }
+            assert_eq!(curve::point_mul_base(&[0; N], curve), curve::infinity());
+            assert_eq!(curve::point_mul_base(curve.order(), curve), curve::infinity());
+        }
+
+        let mut state = 0xBA5E;
+        check(&dstu_params::curve_257(), &mut state);
+        check(&dstu_params::curve_431(), &mut state);
+
+        // base written after Curve::new is not read from stale tables
+        let mut curve = dstu_params::curve_257();
+        let base = curve.base.clone();
+        curve.base = curve::point_dbl(&base, &curve);
+        let factor = random_words(&mut state, 160);
+        assert_eq!(
+            curve::point_mul_base(&factor, &curve),
+            curve::point_mul(&curve.base, &factor, &curve)
+        );
+        assert_ne!(curve::point_mul_base(&factor, &curve), curve::point_mul(&base, &factor, &curve));
+    }
+
+    /* sect571k1 (SEC 2), too wide for gf2m::Field. */
+    #[test]
+    fn test_curve_571() {
+        const WORDS: usize = gf2m::field_words(571);
+        let field = BinaryField::<WORDS>::new(571, 10, 5, 2).unwrap();
+        let curve = Curve::new(
+            [0; WORDS],
+            gf2m::one_words(),
+            gf2m::parse_hex_words(b"020000000000000000000000000000000000000000000000000000000000000000000000131850E1F19A63E4B391A8DB917F4138B630D84BE5D639381E91DEB45CFE778F637C1001"),
+            Point {
+                x: gf2m::parse_hex_words(b"026EB7A859923FBC82189631F8103FE4AC9CA2970012D5D46024804801841CA44370958493B205E647DA304DB4CEB08CBBD1BA39494776FB988B47174DCA88C7E2945283A01C8972"),
+                y: gf2m::parse_hex_words(b"0349DC807F4FBF374F4AEADE3BCA95314DD58CEC9F307A54FFC61EFC006D8A2C9D4979C0AC44AEA74FBEBBB9F772AEDCB620B01A7BA7AF1B320430C8591984F601CD4C143EF1C7A3"),
+            },
+            field,
+        );
+        assert_eq!(curve.field_m(), 571);
+        assert_eq!(curve.terms(), (571, 10, 5, 2));
+        assert_eq!(*curve.modulus(), gf2m::compute_modulus_words(571, 10, 5, 2));
+        assert!(curve::is_on_curve(curve.base(), &curve));
+        assert_eq!(curve::point_mul_base(curve.order(), &curve), curve::infinity());
+
+        let factor = gf2m::parse_hex_words(b"1234567890abcdef1234567890abcdef1234567890abcdef");
+        let expect = Point {
+            x: gf2m::parse_hex_words(b"37b27fdc25be769e502860943eba7608df1d727f3899d422e0bce48039847086774693b0a26635c606c21a9cb0cb9b2f449812b74551ecca1fdde0677dcc5afe2c2da486fb62954"),
+            y: gf2m::parse_hex_words(b"544deb39251cee186e619ad72ed7e9257ae93d93f824c784813d7b7d1daa5451e7d6197fc84634601ab85a5e898106a597dc2ed19d51bd3fc6edc085002e5067caea02e1dc98559"),
+        };
+        assert_eq!(curve::point_mul_base(&factor, &curve), expect);
+        assert_eq!(curve::point_mul(curve.base(), &factor, &curve), expect);
+        assert!(curve::is_valid_public(&expect, &curve));
+    }
+
+    #[test]
+    fn test_curve_onb_backend() {
+        let mut state = 0xBBACE;
+        let curve = dstu_params::curve_431();
+        let conversion = BasisConversion::new(431, curve.field().modulus()).unwrap();
+        let to_normal = |point: &Point<14>| Point {
+            x: conversion.to_normal(&point.x),
+            y: conversion.to_normal(&point.y),
+        };
+        let onb_curve = Curve::new(
+            conversion.to_normal(curve.param_a()),
+            conversion.to_normal(curve.param_b()),
+            *curve.order(),
+            to_normal(curve.base()),
+            OnbField::new(431).unwrap(),
+        );
+
+        let factor = random_words(&mut state, 160);
+        let expect = curve::point_mul_base(&factor, &curve);
+        assert_eq!(curve::point_mul_base(&factor, &onb_curve), to_normal(&expect));
+        assert_eq!(curve::point_mul(onb_curve.base(), &factor, &onb_curve), to_normal(&expect));
+        assert_eq!(curve::point_mul_base(onb_curve.order(), &onb_curve), curve::infinity());
+    }
+
+    #[test]
+    fn test_batch_field() {
+        let mut state = 0xB175;
+        let field = BinaryField::new(257, 12, 0, 0).unwrap();
+        let batch = BatchField::from_field(&field);
+        let values_a: Vec<_> = (0..5).map(|_| random_field(&mut state, 257)).collect();
+        let mut values_b: Vec<_> = (0..5).map(|_| random_field(&mut state, 257)).collect();
+        values_b[4] = gf2m::zero();
+        let sliced_a = batch.pack(&values_a);
+        let sliced_b = batch.pack(&values_b);
+
+        assert_eq!(batch.unpack(&sliced_a, 5), values_a);
+        let product = batch.unpack(&batch.mul(&sliced_a, &sliced_b), 5);
+        let square = batch.unpack(&batch.sqr(&sliced_a), 5);
+        let scaled = batch.unpack(&batch.mul_const(&sliced_a, &values_b[0]), 5);
+        let inverse = batch.unpack(&batch.inv(&sliced_b), 5);
+        for i in 0..5 {
+            assert_eq!(product[i], field.mul(&values_a[i], &values_b[i]));
+            assert_eq!(square[i], field.sqr(&values_a[i]));
+            assert_eq!(scaled[i], field.mul(&values_a[i], &values_b[0]));
+        }
+        for i in 0..4 {
+            assert_eq!(inverse[i], field.inv(&values_b[i]));
+        }
+        assert_eq!(inverse[4], gf2m::zero());
+        assert_eq!(batch.is_zero(&sliced_b) & 0x1F, 1 << 4);
+    }
+
+    #[test]
+    fn test_point_mul_batch() {
+        let mut state = 0xBA7C;
+        let curve = dstu_params::curve_257();
+        let mut points = Vec::new();
+        let mut factors = Vec::new();
+        for _ in 0..4 {
+            points.push(curve::point_mul_base(&random_words(&mut state, 32), &curve));
+            factors.push(random_words(&mut state, 160));
+        }
+        // zero, -P and infinity lanes
+        factors[1] = [0; 9];
+        // order is odd
+        factors[2] = *curve.order();
+        factors[2][0] ^= 1;
+        points[3] = curve::infinity();
+
+        let batched = bitslice::point_mul_batch(&points, &factors, &curve);
+        for i in 0..points.len() {
+            assert_eq!(batched[i], curve::point_mul(&points[i], &factors[i], &curve));
+        }
+    }
+
+    #[test]
+    fn test_point_mul_ct() {
+        let curve = dstu_params::curve_257();
+        let privd = gf2m::parse_hex_words(b"2A45EAFE4CD469F811737780C57253360FBCC58E134C9A1FDCD10B0E4529A143");
+
+        let point = Point {
+            x: gf2m::parse_hex_words(b"00AFF3EE09CB429284985849E20DE5742E194AA631490F62BA88702505629A6589"),
+            y: gf2m::parse_hex_words(b"01B345BC134F27DA251EDFAE97B3F306B4E8B8CB9CF86D8651E4FB301EF8E1239C")
+        };
+
+        let expect = Point {
+            x: gf2m::parse_hex_words(b"8c3d388b1c51116cf0ed041718309b360f775d8df86e9fc141822e79a3b0da8b"),
+            y: gf2m::parse_hex_words(b"a8624188d9f4ab0afafbde6230cd8cf7c28b38f42fcbb4021ff0c0244a5ddbbd")
+        };
+        assert_eq!(curve::point_mul_ct(&point, &privd, &curve), expect);
+
+        // factors above the order are reduced first
+        let (privd_n, _) = ct::int_add(&privd, curve.order());
+        let (privd_2n, _) = ct::int_add(&privd_n, curve.order());
+        assert_eq!(ct::int_reduce(&privd_2n, curve.order()), privd);
+        assert_eq!(curve::point_mul_ct(&point, &privd_n, &curve), expect);
+        assert_eq!(curve::point_mul_ct(&point, &privd_2n, &curve), expect);
+        assert_eq!(curve::point_mul_ct(&point, &[0; 9], &curve), curve::infinity());
+    }
+
     #[test]
     fn test_point_expand() {
         let curve = dstu_params::curve_431();
@@ -243,14 +1327,48 @@ mod test {
             0xeb, 0x1e, 0xb1, 0x9a, 0xd5, 0x44
         ];
 
-        let point_compressed_x = gf2m::from_bytes_le(&point_data);
+        let point_compressed_x = gf2m::from_bytes_le_words(&point_data);
         let point = curve::point_expand(&point_compressed_x, &curve);
-        assert_eq!(point, Point {
-            x: gf2m::parse_hex(b"44d59ab11eebf43534911d05992fc24fa8c6d0da68276eaf1bcde5dc6767705b527b007e700da85f1dc5f550392cabca624bbdf91bb7"),
-            y: gf2m::parse_hex(b"6edb5b3e38bf271233378ac0fe3990289007928f56beb38a4f63843b9995afdd88a09c7da6935a4b43b0afde65a4ca9c159d72ed5275"),
+        assert_eq!(point.unwrap(), Point {
+            x: gf2m::parse_hex_words(b"44d59ab11eebf43534911d05992fc24fa8c6d0da68276eaf1bcde5dc6767705b527b007e700da85f1dc5f550392cabca624bbdf91bb7"),
+            y: gf2m::parse_hex_words(b"6edb5b3e38bf271233378ac0fe3990289007928f56beb38a4f63843b9995afdd88a09c7da6935a4b43b0afde65a4ca9c159d72ed5275"),
         });
     }
 
+    #[test]
+    fn test_point_expand_zero() {
+        let curve = dstu_params::curve_257();
+        let point = curve::point_expand(&[0; 9], &curve).unwrap();
+
+        assert_eq!(point.x, [0; 9]);
+        assert_eq!(curve.field().sqr(&point.y), *curve.param_b());
+    }
+
+    #[test]
+    fn test_point_expand_invalid() {
+        let curve = dstu_params::curve_257();
+        let mut state = 0xBAD;
+        let mut rejected = 0;
+        for _ in 0..16 {
+            let compressed = random_words(&mut state, 257);
+            match curve::point_expand(&compressed, &curve) {
+                Some(point) => {
+                    // y^2 + xy = x^3 + ax^2 + b
+                    let field = curve.field();
+                    let x2 = field.sqr(&point.x);
+                    let lhs = field.add(&field.sqr(&point.y), &field.mul(&point.x, &point.y));
+                    let mut rhs = field.add(&field.mul(&x2, &point.x), curve.param_b());
+                    if curve.param_a()[0] == 1 {
+                        rhs = field.add(&rhs, &x2);
+                    }
+                    assert_eq!(lhs, rhs);
+                },
+                None => rejected += 1,
+            }
+        }
+        assert!(rejected > 0);
+    }
+
     #[test]
     fn test_point_expand_even() {
         let curve = dstu_params::curve_431();
@@ -264,12 +1382,12 @@ mod test {
             0xeb, 0x1e, 0xb1, 0x9a, 0xd5, 0x44
         ];
 
-        let point_compressed_x = gf2m::from_bytes_le(&point_data);
+        let point_compressed_x = gf2m::from_bytes_le_words(&point_data);
         let point = curve::point_expand(&point_compressed_x, &curve);
 
-        assert_eq!(point, Point {
-            x: gf2m::parse_hex(b"44d59ab11eebf43534911d05992fc24fa8c6d0da68276eaf1bcde5dc6767705b527b007e700da85f1dc5f550392cabca624bbdf91bb7"),
-            y: gf2m::parse_hex(b"2a0ec18f2654d32707a697c56716526738c142553e99dd2554ae61e7fef2df86dadb9c03d69ef2145e755a8e5c88615677d6cf1449c2"),
+        assert_eq!(point.unwrap(), Point {
+            x: gf2m::parse_hex_words(b"44d59ab11eebf43534911d05992fc24fa8c6d0da68276eaf1bcde5dc6767705b527b007e700da85f1dc5f550392cabca624bbdf91bb7"),
+            y: gf2m::parse_hex_words(b"2a0ec18f2654d32707a697c56716526738c142553e99dd2554ae61e7fef2df86dadb9c03d69ef2145e755a8e5c88615677d6cf1449c2"),
         });
     }
 }
@@ -0,0 +1,441 @@
+/* GF(2^m) in type II optimal normal basis, as allowed for DSTU 4145
+   domain parameters. Bit i of an element is the coefficient of
+   beta^(2^i), where beta = g + 1/g for a primitive p-th root of unity
+   g and p = 2m + 1 prime.
+
+   Squaring is a cyclic shift of the bits. Multiplication maps both
+   operands to coefficients of powers of g, multiplies them as
+   polynomials modulo x^p - 1 and maps the result back:
+   beta^(2^i) = g^j + g^(p - j) with j = 2^i mod p, and 1 is the sum
+   of all basis elements, so the x^0 coefficient of the product
+   is added to every bit. */
+
+use gf2m;
+use gf2m::{Field, FIELD_SIZE};
+use field::{FieldBackend, FieldError};
+
+const WORD_SIZE: usize = 32;
+
+/* Room for polynomials of degree below p, p < 1024. Only the
+   words actually needed for p are multiplied. */
+const CYCLE_SIZE: usize = gf2m::MAX_WORDS;
+
+type Cycle = [u32; CYCLE_SIZE];
+
+/* Elements are N words like BinaryField<N>, see gf2m::field_words. */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OnbField<const N: usize = FIELD_SIZE> {
+    field_m: usize,
+    // 2^i mod p for bit i
+    position: Vec<usize>,
+    // ceil(p / 32), words of a cycle in use
+    cycle_words: usize,
+    // sum of all basis elements
+    one: [u32; N],
+}
+
+fn is_prime(value: usize) -> bool {
+    if value < 2 {
+        return false;
+    }
+    let mut d = 2;
+    while d * d <= value {
+        if value % d == 0 {
+            return false;
+        }
+        d += 1;
+    }
+    return true;
+}
+
+#[inline]
+fn get_bit(value: &[u32], bit: usize) -> u32 {
+    return (value[bit / WORD_SIZE] >> (bit % WORD_SIZE)) & 1;
+}
+
+#[inline]
+fn put_bit(value: &mut [u32], bit: usize, set: u32) {
+    value[bit / WORD_SIZE] |= set << (bit % WORD_SIZE);
+}
+
+impl<const N: usize> OnbField<N> {
+    /* Type II ONB exists when p = 2m + 1 is prime and powers of 2
+       reach every residue up to sign. */
+    pub fn new(field_m: usize) -> Result<OnbField<N>, FieldError> {
+        let p = field_m * 2 + 1;
+        if field_m >= N * WORD_SIZE || p > CYCLE_SIZE * WORD_SIZE {
+            return Err(FieldError::Degree);
+        }
+        if field_m < 2 || !is_prime(p) {
+            return Err(FieldError::NoOptimalBasis);
+        }
+
+        let mut seen = vec![false; field_m + 1];
+        let mut position = Vec::with_capacity(field_m);
+        let mut power = 1;
+        for _ in 0..field_m {
+            let folded = if power > field_m { p - power } else { power };
+            if seen[folded] {
+                return Err(FieldError::NoOptimalBasis);
+            }
+            seen[folded] = true;
+            position.push(power);
+            power = power * 2 % p;
+        }
+
+        let mut one = [0; N];
+        for i in 0..field_m {
+            put_bit(&mut one, i, 1);
+        }
+
+        return Ok(OnbField {
+            field_m: field_m,
+            position: position,
+            cycle_words: p.div_ceil(WORD_SIZE),
+            one: one,
+        });
+    }
+
+    pub fn degree(&self) -> usize {
+        return self.field_m;
+    }
+
+    pub fn zero(&self) -> [u32; N] {
+        return [0; N];
+    }
+
+    /* Sum of all basis elements. */
+    pub fn one(&self) -> [u32; N] {
+        return self.one;
+    }
+
+    /* Clears bits at and above m. */
+    pub fn reduce(&self, value: &[u32]) -> [u32; N] {
+        let mut ret = [0; N];
+        for i in 0..self.field_m {
+            put_bit(&mut ret, i, get_bit(value, i));
+        }
+        return ret;
+    }
+
+    pub fn add(&self, value_a: &[u32; N], value_b: &[u32; N]) -> [u32; N] {
+        return gf2m::add_words(value_a, value_b);
+    }
+
+    /* value^(2^k), bits rotated up by k. */
+    pub fn frobenius(&self, value: &[u32; N], k: usize) -> [u32; N] {
+        let k = k % self.field_m;
+        let mut ret = [0; N];
+        for i in 0..self.field_m {
+            put_bit(&mut ret, (i + k) % self.field_m, get_bit(value, i));
+        }
+        return ret;
+    }
+
+    pub fn sqr(&self, value: &[u32; N]) -> [u32; N] {
+        return self.frobenius(value, 1);
+    }
+
+    pub fn sqrt(&self, value: &[u32; N]) -> [u32; N] {
+        return self.frobenius(value, self.field_m - 1);
+    }
+
+    fn to_cycle(&self, value: &[u32; N]) -> Cycle {
+        let p = self.field_m * 2 + 1;
+        let mut ret = [0; CYCLE_SIZE];
+        for i in 0..self.field_m {
+            let bit = get_bit(value, i);
+            put_bit(&mut ret, self.position[i], bit);
+            put_bit(&mut ret, p - self.position[i], bit);
+        }
+        return ret;
+    }
+
+    pub fn mul(&self, value_a: &[u32; N], value_b: &[u32; N]) -> [u32; N] {
+        let p = self.field_m * 2 + 1;
+        let words = self.cycle_words;
+        let mut product = [0; CYCLE_SIZE * 2];
+        gf2m::mul_slices(
+            &self.to_cycle(value_a)[..words], &self.to_cycle(value_b)[..words],
+            &mut product[..words * 2]
+        );
+
+        let constant = get_bit(&product, 0) ^ get_bit(&product, p);
+        let mut ret = [0; N];
+        for i in 0..self.field_m {
+            let j = self.position[i];
+            // x^p = 1
+            put_bit(&mut ret, i, get_bit(&product, j) ^ get_bit(&product, j + p) ^ constant);
+        }
+        return ret;
+    }
+
+    /* Itoh-Tsujii chain as gf2m::inv, squarings are free here.
+       Inverse of zero is zero. */
+    pub fn inv(&self, value: &[u32; N]) -> [u32; N] {
+        let chain = self.field_m - 1;
+        let mut bit = (usize::BITS - chain.leading_zeros()) as usize - 1;
+        let mut beta = self.reduce(value);
+        let mut k = 1;
+
+        while bit > 0 {
+            bit -= 1;
+            beta = self.mul(&self.frobenius(&beta, k), &beta);
+            k *= 2;
+
+            if (chain >> bit) & 1 == 1 {
+                beta = self.mul(&self.sqr(&beta), value);
+                k += 1;
+            }
+        }
+
+        return self.sqr(&beta);
+    }
+
+    /* Every basis element has trace one, so the trace
+       is parity of the bits. */
+    pub fn trace(&self, value: &[u32; N]) -> u32 {
+        let mut acc = 0;
+        for i in 0..N {
+            acc ^= value[i];
+        }
+        return acc.count_ones() & 1;
+    }
+
+    /* Root of z^2 + z = beta, z_0 = 0 and z_i = z_(i-1) + beta_i.
+       The other root is z + 1. None when Tr(beta) = 1. */
+    pub fn solve_quadratic(&self, beta: &[u32; N]) -> Option<[u32; N]> {
+        let mut ret = [0; N];
+        let mut bit = 0;
+        for i in 1..self.field_m {
+            bit ^= get_bit(beta, i);
+            put_bit(&mut ret, i, bit);
+        }
+        if self.trace(beta) == 1 {
+            return None;
+        }
+        return Some(ret);
+    }
+}
+
+impl<const N: usize> FieldBackend<N> for OnbField<N> {
+    /* Nothing to precompute, the convolution takes both operands. */
+    type Prepared = [u32; N];
+
+    fn degree(&self) -> usize {
+        return self.field_m;
+    }
+
+    fn unity(&self) -> [u32; N] {
+        return self.one();
+    }
+
+    fn reduce(&self, value: &[u32]) -> [u32; N] {
+        return OnbField::reduce(self, value);
+    }
+
+    fn mul(&self, value_a: &[u32; N], value_b: &[u32; N]) -> [u32; N] {
+        return OnbField::mul(self, value_a, value_b);
+    }
+
+    fn sqr(&self, value: &[u32; N]) -> [u32; N] {
+        return OnbField::sqr(self, value);
+    }
+
+    fn sqrt(&self, value: &[u32; N]) -> [u32; N] {
+        return OnbField::sqrt(self, value);
+    }
+
+    fn inv(&self, value: &[u32; N]) -> [u32; N] {
+        return OnbField::inv(self, value);
+    }
+
+    fn trace(&self, value: &[u32; N]) -> u32 {
+        return OnbField::trace(self, value);
+    }
+
+    fn solve_quadratic(&self, beta: &[u32; N]) -> Option<[u32; N]> {
+        return OnbField::solve_quadratic(self, beta);
+    }
+
+    fn prepare(&self, value: &[u32; N]) -> Self::Prepared {
+        return *value;
+    }
+
+    fn mul_prepared(&self, prepared: &[u32; N], value: &[u32; N]) -> [u32; N] {
+        return OnbField::mul(self, prepared, value);
+    }
+}
+
+/* Element of GF(2^2m) = F[z] / (z^2 + z + t) as (a0, a1) = a0 + a1 z,
+   F in polynomial basis. Only used to find roots of unity. */
+type Quadratic = (Field, Field);
+
+fn quadratic_mul(value_a: &Quadratic, value_b: &Quadratic, t: &Field, modulus: &Field) -> Quadratic {
+    let mul = |a: &Field, b: &Field| gf2m::reduce(&gf2m::mul_fast(a, b), modulus);
+    let a0b0 = mul(&value_a.0, &value_b.0);
+    let a1b1 = mul(&value_a.1, &value_b.1);
+    let cross = mul(&gf2m::add(&value_a.0, &value_a.1), &gf2m::add(&value_b.0, &value_b.1));
+    // z^2 = z + t
+    return (
+        gf2m::add(&a0b0, &mul(&a1b1, t)),
+        gf2m::add(&cross, &a0b0),
+    );
+}
+
+fn quadratic_pow(value: &Quadratic, exponent: &[u32], t: &Field, modulus: &Field) -> Quadratic {
+    let mut result = (gf2m::one(), gf2m::zero());
+    let mut bit = gf2m::bit_size(exponent);
+    while bit > 0 {
+        bit -= 1;
+        result = quadratic_mul(&result, &result, t, modulus);
+        if gf2m::has_bit(exponent, bit) {
+            result = quadratic_mul(&result, value, t, modulus);
+        }
+    }
+    return result;
+}
+
+/* (2^bits - 1) / divisor, little-endian words. */
+fn mersenne_div(bits: usize, divisor: u32) -> Vec<u32> {
+    let mut ret = vec![0; bits.div_ceil(WORD_SIZE)];
+    let mut rest: u64 = 0;
+    for i in (0..ret.len()).rev() {
+        let word = if (i + 1) * WORD_SIZE <= bits {
+            0xFF_FF_FF_FF
+        } else {
+            (1u32 << (bits % WORD_SIZE)) - 1
+        };
+        let current = (rest << WORD_SIZE) | word as u64;
+        ret[i] = (current / divisor as u64) as u32;
+        rest = current % divisor as u64;
+    }
+    return ret;
+}
+
+/* Change of basis between polynomial basis with the given modulus
+   and type II optimal normal basis of the same degree.
+
+   The normal basis generator beta = g + 1/g is found in polynomial
+   basis through a primitive p-th root of unity g, which lives in the
+   quadratic extension: g = w^((2^2m - 1) / p) for the first w = z + x^i
+   that does not give 1. Other roots give conjugates of the same beta,
+   the choice only rotates normal basis coordinates. Search runs on
+   full gf2m::Field words, the tables hold N words. */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BasisConversion<const N: usize = FIELD_SIZE> {
+    field_m: usize,
+    // beta^(2^i) in polynomial basis
+    to_poly: Vec<[u32; N]>,
+    // x^i in normal basis
+    to_normal: Vec<[u32; N]>,
+}
+
+impl<const N: usize> BasisConversion<N> {
+    pub fn new(field_m: usize, modulus: &[u32; N]) -> Result<BasisConversion<N>, FieldError> {
+        if gf2m::bit_size(modulus) != field_m + 1 || field_m >= FIELD_SIZE * WORD_SIZE {
+            return Err(FieldError::Degree);
+        }
+        match OnbField::<N>::new(field_m) {
+            Ok(_) => {},
+            Err(err) => return Err(err),
+        };
+        let p = field_m * 2 + 1;
+        let modulus: &Field = &gf2m::resize_words(modulus);
+
+        // z^2 + z + t is irreducible over F when Tr(t) = 1
+        let mask = gf2m::trace_mask(modulus);
+        let mut t = gf2m::one();
+        while gf2m::trace_masked(&t, &mask) == 0 {
+            t = gf2m::reduce(&gf2m::shl(&t, 1), modulus);
+        }
+
+        let exponent = mersenne_div(field_m * 2, p as u32);
+        let mut w = (gf2m::one(), gf2m::one());
+        let gamma = loop {
+            let gamma = quadratic_pow(&w, &exponent, &t, modulus);
+            if gamma != (gf2m::one(), gf2m::zero()) {
+                break gamma;
+            }
+            w.0 = gf2m::reduce(&gf2m::shl(&w.0, 1), modulus);
+        };
+
+        // 1/g = g^(p - 1), beta lands in F
+        let gamma_inv = quadratic_pow(&gamma, &[(p - 1) as u32], &t, modulus);
+        let beta = (gf2m::add(&gamma.0, &gamma_inv.0), gf2m::add(&gamma.1, &gamma_inv.1));
+        assert!(gf2m::is_zero(&beta.1));
+
+        let mut to_poly = Vec::with_capacity(field_m);
+        let mut power = beta.0;
+        for _ in 0..field_m {
+            to_poly.push(gf2m::resize_words(&power));
+            power = gf2m::reduce(&gf2m::sqr_fast(&power), modulus);
+        }
+
+        let to_normal = match invert(&to_poly, field_m) {
+            Some(rows) => rows,
+            None => return Err(FieldError::NoOptimalBasis),
+        };
+
+        return Ok(BasisConversion {
+            field_m: field_m,
+            to_poly: to_poly,
+            to_normal: to_normal,
+        });
+    }
+
+    pub fn degree(&self) -> usize {
+        return self.field_m;
+    }
+
+    /* Normal basis coordinates to polynomial basis. */
+    pub fn to_polynomial(&self, value: &[u32; N]) -> [u32; N] {
+        return combine(&self.to_poly, value);
+    }
+
+    /* Polynomial basis to normal basis coordinates, value has
+       to be reduced. */
+    pub fn to_normal(&self, value: &[u32; N]) -> [u32; N] {
+        return combine(&self.to_normal, value);
+    }
+}
+
+/* Sum of rows[i] for every bit i set in value, constant time. */
+fn combine<const N: usize>(rows: &[[u32; N]], value: &[u32; N]) -> [u32; N] {
+    let mut ret = [0; N];
+    for i in 0..rows.len() {
+        let choice = 0u32.wrapping_sub(get_bit(value, i));
+        for j in 0..N {
+            ret[j] ^= rows[i][j] & choice;
+        }
+    }
+    return ret;
+}
+
+/* Rows of the inverse of the linear map sending e_i to columns[i],
+   by Gauss-Jordan elimination over GF(2). None when singular. */
+fn invert<const N: usize>(columns: &[[u32; N]], size: usize) -> Option<Vec<[u32; N]>> {
+    let mut vectors: Vec<([u32; N], [u32; N])> = Vec::with_capacity(size);
+    for i in 0..size {
+        let mut tag = [0; N];
+        put_bit(&mut tag, i, 1);
+        vectors.push((columns[i], tag));
+    }
+
+    for bit in 0..size {
+        let pivot = match (bit..size).find(|&k| get_bit(&vectors[k].0, bit) == 1) {
+            Some(pivot) => pivot,
+            None => return None,
+        };
+        vectors.swap(bit, pivot);
+        let (value, tag) = vectors[bit];
+        for k in 0..size {
+            if k != bit && get_bit(&vectors[k].0, bit) == 1 {
+                vectors[k].0 = gf2m::add_words(&vectors[k].0, &value);
+                vectors[k].1 = gf2m::add_words(&vectors[k].1, &tag);
+            }
+        }
+    }
+
+    return Some(vectors.into_iter().map(|(_, tag)| tag).collect());
+}
@@ -0,0 +1,260 @@
+/* Polynomials over GF(2) of any degree, bit i of the word vector
+   is the coefficient of x^i. Used to validate field polynomials,
+   element arithmetic lives in gf2m. Results are trimmed: no zero
+   words on top, zero polynomial is an empty vector. */
+
+use gf2m;
+
+const WORD_SIZE: usize = 32;
+
+pub type Poly = Vec<u32>;
+
+/* gf2m::bit_size for possibly empty vectors. */
+fn bit_size(value: &[u32]) -> usize {
+    if value.is_empty() {
+        return 0;
+    }
+    return gf2m::bit_size(value);
+}
+
+pub fn trim(value: &mut Poly) {
+    while value.last() == Some(&0) {
+        value.pop();
+    }
+}
+
+pub fn from_words(value: &[u32]) -> Poly {
+    let mut ret = value.to_vec();
+    trim(&mut ret);
+    return ret;
+}
+
+/* x^m + x^k1 + x^k2 + x^k3 + 1, as in gf2m::compute_modulus. */
+pub fn from_terms(field_m: usize, k1: usize, k2: usize, k3: usize) -> Poly {
+    let mut ret = vec![0; field_m / WORD_SIZE + 1];
+    for &bit in [field_m, k1, k2, k3].iter() {
+        ret[bit / WORD_SIZE] |= 1 << (bit % WORD_SIZE);
+    }
+    ret[0] |= 1;
+    return ret;
+}
+
+pub fn one() -> Poly {
+    return vec![1];
+}
+
+/* x, the indeterminate. */
+pub fn x() -> Poly {
+    return vec![2];
+}
+
+pub fn is_zero(value: &[u32]) -> bool {
+    return value.iter().all(|&word| word == 0);
+}
+
+pub fn is_one(value: &[u32]) -> bool {
+    return !value.is_empty() && value[0] == 1 && is_zero(&value[1..]);
+}
+
+/* None for the zero polynomial. */
+pub fn degree(value: &[u32]) -> Option<usize> {
+    let size = bit_size(value);
+    if size == 0 {
+        return None;
+    }
+    return Some(size - 1);
+}
+
+pub fn add(value_a: &[u32], value_b: &[u32]) -> Poly {
+    let (long, short) = if value_a.len() >= value_b.len() {
+        (value_a, value_b)
+    } else {
+        (value_b, value_a)
+    };
+    let mut ret = long.to_vec();
+    for i in 0..short.len() {
+        ret[i] ^= short[i];
+    }
+    trim(&mut ret);
+    return ret;
+}
+
+/* value_a += value_b * x^shift */
+fn add_shifted(value_a: &mut Poly, value_b: &[u32], shift: usize) {
+    let word_shift = shift / WORD_SIZE;
+    let bit_shift = shift % WORD_SIZE;
+    let size = value_b.len() + word_shift + 1;
+    if value_a.len() < size {
+        value_a.resize(size, 0);
+    }
+    for i in 0..value_b.len() {
+        value_a[i + word_shift] ^= value_b[i] << bit_shift;
+        if bit_shift > 0 {
+            value_a[i + word_shift + 1] ^= value_b[i] >> (WORD_SIZE - bit_shift);
+        }
+    }
+}
+
+pub fn mul(value_a: &[u32], value_b: &[u32]) -> Poly {
+    let mut ret = Vec::new();
+    for bit in 0..bit_size(value_a) {
+        if gf2m::has_bit(value_a, bit) {
+            add_shifted(&mut ret, value_b, bit);
+        }
+    }
+    trim(&mut ret);
+    return ret;
+}
+
+/* Low half-word of value with a zero inserted after each bit. */
+#[inline]
+fn spread_u16(value: u32) -> u32 {
+    let mut x = value & 0x00_00_FF_FF;
+    x = (x | (x << 8)) & 0x00_FF_00_FF;
+    x = (x | (x << 4)) & 0x0F_0F_0F_0F;
+    x = (x | (x << 2)) & 0x33_33_33_33;
+    x = (x | (x << 1)) & 0x55_55_55_55;
+    return x;
+}
+
+pub fn sqr(value: &[u32]) -> Poly {
+    let mut ret = Vec::with_capacity(value.len() * 2);
+    for &word in value.iter() {
+        ret.push(spread_u16(word));
+        ret.push(spread_u16(word >> 16));
+    }
+    trim(&mut ret);
+    return ret;
+}
+
+/* Quotient and remainder, panics on division by zero. */
+pub fn divmod(value: &[u32], divisor: &[u32]) -> (Poly, Poly) {
+    let div_degree = degree(divisor).expect("division by zero polynomial");
+    let divisor = from_words(divisor);
+    let mut rem = from_words(value);
+    let mut quot = vec![0; rem.len()];
+
+    let mut bit = bit_size(&rem);
+    while bit > div_degree {
+        bit -= 1;
+        if gf2m::has_bit(&rem, bit) {
+            let shift = bit - div_degree;
+            add_shifted(&mut rem, &divisor, shift);
+            quot[shift / WORD_SIZE] |= 1 << (shift % WORD_SIZE);
+        }
+    }
+    trim(&mut rem);
+    trim(&mut quot);
+    return (quot, rem);
+}
+
+pub fn rem(value: &[u32], divisor: &[u32]) -> Poly {
+    return divmod(value, divisor).1;
+}
+
+pub fn gcd(value_a: &[u32], value_b: &[u32]) -> Poly {
+    let mut a = from_words(value_a);
+    let mut b = from_words(value_b);
+    while !b.is_empty() {
+        let r = rem(&a, &b);
+        a = b;
+        b = r;
+    }
+    return a;
+}
+
+pub fn mulmod(value_a: &[u32], value_b: &[u32], modulus: &[u32]) -> Poly {
+    return rem(&mul(value_a, value_b), modulus);
+}
+
+pub fn sqrmod(value: &[u32], modulus: &[u32]) -> Poly {
+    return rem(&sqr(value), modulus);
+}
+
+/* value^exponent mod modulus, exponent is a little-endian
+   multiword integer. */
+pub fn powmod(value: &[u32], exponent: &[u32], modulus: &[u32]) -> Poly {
+    let value = rem(value, modulus);
+    let mut result = rem(&one(), modulus);
+    let mut bit = bit_size(exponent);
+    while bit > 0 {
+        bit -= 1;
+        result = sqrmod(&result, modulus);
+        if gf2m::has_bit(exponent, bit) {
+            result = mulmod(&result, &value, modulus);
+        }
+    }
+    return result;
+}
+
+/* x^(2^k) mod modulus for k = 0..=count, by repeated squaring. */
+fn frobenius_powers(modulus: &[u32], count: usize) -> Vec<Poly> {
+    let mut ret = Vec::with_capacity(count + 1);
+    let mut power = rem(&x(), modulus);
+    ret.push(power.clone());
+    for _ in 0..count {
+        power = sqrmod(&power, modulus);
+        ret.push(power.clone());
+    }
+    return ret;
+}
+
+fn prime_factors(value: usize) -> Vec<usize> {
+    let mut ret = Vec::new();
+    let mut rest = value;
+    let mut p = 2;
+    while p * p <= rest {
+        if rest % p == 0 {
+            ret.push(p);
+            while rest % p == 0 {
+                rest /= p;
+            }
+        }
+        p += 1;
+    }
+    if rest > 1 {
+        ret.push(rest);
+    }
+    return ret;
+}
+
+/* Rabin's test: f of degree n is irreducible iff x^(2^n) = x mod f
+   and gcd(x^(2^(n/p)) - x, f) = 1 for every prime p dividing n. */
+pub fn is_irreducible_rabin(modulus: &[u32]) -> bool {
+    let n = match degree(modulus) {
+        Some(n) if n > 0 => n,
+        _ => return false,
+    };
+    let powers = frobenius_powers(modulus, n);
+    let x = rem(&x(), modulus);
+
+    if powers[n] != x {
+        return false;
+    }
+    for p in prime_factors(n) {
+        if !is_one(&gcd(&add(&powers[n / p], &x), modulus)) {
+            return false;
+        }
+    }
+    return true;
+}
+
+/* Ben-Or's test: f of degree n is irreducible iff it has no factor
+   of degree i <= n/2, that is gcd(x^(2^i) - x, f) = 1 for all such i.
+   Stops at the first factor found, which is fast for random
+   reducible polynomials. */
+pub fn is_irreducible_ben_or(modulus: &[u32]) -> bool {
+    let n = match degree(modulus) {
+        Some(n) if n > 0 => n,
+        _ => return false,
+    };
+    let x = rem(&x(), modulus);
+    let mut power = x.clone();
+    for _ in 0..(n / 2) {
+        power = sqrmod(&power, modulus);
+        if !is_one(&gcd(&add(&power, &x), modulus)) {
+            return false;
+        }
+    }
+    return true;
+}
@@ -0,0 +1,51 @@
+/* Sampling of field elements and scalars from a cryptographically
+   secure generator, for key generation and signing nonces. Values
+   are drawn bit-exact and rejected until they fall into range, so
+   the result is uniform. */
+
+use rand_core::{CryptoRng, RngCore};
+
+use ct;
+use gf2m;
+
+/* Uniform value of the given bit length or less. */
+fn random_bits<const N: usize, R: RngCore + CryptoRng>(rng: &mut R, bits: usize) -> [u32; N] {
+    assert!(bits <= N * 32);
+    let mut ret = [0; N];
+    let words = bits.div_ceil(32);
+    for i in 0..words {
+        ret[i] = rng.next_u32();
+    }
+    if bits % 32 != 0 {
+        ret[words - 1] &= (1 << (bits % 32)) - 1;
+    }
+    return ret;
+}
+
+/* Uniform field element, degree < field_m. */
+pub fn random_field<const N: usize, R: RngCore + CryptoRng>(rng: &mut R, field_m: usize) -> [u32; N] {
+    return random_bits(rng, field_m);
+}
+
+/* Uniform nonzero field element. */
+pub fn random_field_nonzero<const N: usize, R: RngCore + CryptoRng>(rng: &mut R, field_m: usize) -> [u32; N] {
+    loop {
+        let value = random_bits(rng, field_m);
+        if !gf2m::is_zero_words(&value) {
+            return value;
+        }
+    }
+}
+
+/* Uniform scalar in [1, order - 1]. Candidates have the bit length
+   of order, so at least half of them are accepted. */
+pub fn random_scalar<const N: usize, R: RngCore + CryptoRng>(rng: &mut R, order: &[u32; N]) -> [u32; N] {
+    assert!(gf2m::bit_size(order) > 1, "order must be above 1");
+    let bits = gf2m::bit_size(order);
+    loop {
+        let value = random_bits(rng, bits);
+        if ct::in_range(&value, order) {
+            return value;
+        }
+    }
+}